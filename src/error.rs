@@ -6,6 +6,7 @@ use thiserror::Error;
 
 /// Errors that can be returned when parsing rule strings.
 #[derive(Clone, Debug, PartialEq, Eq, Error, Display)]
+#[ignore_extra_doc_attributes]
 pub enum ParseRuleError {
     /// Missing expected {0:?}
     Missing(char),
@@ -13,16 +14,154 @@ pub enum ParseRuleError {
     MissingNumber,
     /// Unexpected {0:?}
     Unexpected(char),
-    /// Extra unparsed junk at the end of the rule string
-    ExtraJunk,
+    /// Extra unparsed junk {found:?} at byte {offset} after the end of the rule string
+    ExtraJunk {
+        /// The first character after the end of the rule string.
+        found: char,
+        /// The byte offset of that character in the input.
+        offset: usize,
+    },
     /// Number of states less than 2 in Generations rule
     GenLessThan2,
     /// Not a MAP rule
     NotMapRule,
-    /// Invalid Base64 encoding for MAP rule
-    Base64Error,
-    /// Invalid length for MAP rule
-    InvalidLength,
+    /// Invalid Base64 encoding for MAP rule: {0}
+    Base64Error(#[source] MapDecodeError),
+    /// Invalid length for MAP rule: expected {expected} bits, found {found} bits
+    InvalidLength {
+        /// The number of bits expected for the neighborhood.
+        expected: usize,
+        /// The number of bits decoded from the MAP string.
+        found: usize,
+    },
+    /// Invalid length for MAP rule: found {found} bits, expected 512 (Moore), 128 (hexagonal) or 32 (von Neumann)
+    UnknownMapLength {
+        /// The number of bits decoded from the MAP string.
+        found: usize,
+    },
     /// Generations number overflow for Generations rule
+    ///
+    /// The limit is [`usize::MAX`], so it depends on the target.
     GenOverflow,
+    /// Empty rule string
+    EmptyInput,
+    /// Invalid bounded grid specification
+    InvalidTopology,
+    /// History, Super or Investigator suffix in Generations rule
+    VariantInGen,
+    /// Neighbor count {0} too large for the neighborhood
+    ///
+    /// This is returned for a digit that is out of range for the neighborhood,
+    /// e.g., the `7` in `B3/S27H`, in both totalistic and non-totalistic rules,
+    /// rather than [`Unexpected`](ParseRuleError::Unexpected), which is kept for
+    /// characters that are not digits.
+    CountTooLarge(u8),
+    /// MAP rule depends on neighbors outside the hexagonal neighborhood
+    NotHexagonal,
+    /// Invalid range {0}-{1} of neighbor counts
+    InvalidRange(u8, u8),
+    /// Negation after neighbor count {0} without any valid letter
+    EmptyNegation(u8),
+    /// Neither an RLE header line nor a `#r` line
+    NotRleHeader,
+}
+
+impl ParseRuleError {
+    /// Constructs an [`ExtraJunk`](ParseRuleError::ExtraJunk) error.
+    pub const fn extra_junk(found: char, offset: usize) -> Self {
+        ParseRuleError::ExtraJunk { found, offset }
+    }
+}
+
+/// Errors in the Base64 data of MAP strings.
+///
+/// The offsets are those of the data with whitespace removed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Error, Display)]
+pub enum MapDecodeError {
+    /// Invalid byte {1}, offset {0}.
+    InvalidByte(usize, u8),
+    /// Encoded text cannot have a 6-bit remainder.
+    InvalidLength,
+    /// Invalid last symbol {1}, offset {0}.
+    InvalidLastSymbol(usize, u8),
+    /// Invalid padding
+    InvalidPadding,
+}
+
+/// An error in a rule string, together with the position where it was found.
+///
+/// This is returned by the `parse_rule_at` and `parse_rule_at_with` methods of the parser traits.
+/// The [`kind`](ParseError::kind) is the same error that `parse_rule` and `parse_rule_with`
+/// return for the same input.
+///
+/// # Examples
+///
+/// ```
+/// use ca_rules::{ParseError, ParseLife, ParseRuleError};
+///
+/// struct Rule;
+///
+/// impl ParseLife for Rule {
+///     fn from_bs(_b: Vec<u8>, _s: Vec<u8>) -> Self {
+///         Rule
+///     }
+/// }
+///
+/// let e = Rule::parse_rule_at("B3/X23").err().unwrap();
+/// assert_eq!(
+///     e,
+///     ParseError {
+///         kind: ParseRuleError::Missing('S'),
+///         offset: 3,
+///     }
+/// );
+/// assert_eq!(e.to_string(), "Missing expected 'S' at byte 3");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Error, Display)]
+#[displaydoc("{kind} at byte {offset}")]
+pub struct ParseError {
+    /// The kind of the error.
+    pub kind: ParseRuleError,
+    /// The byte offset in the input where parsing stopped.
+    ///
+    /// This is usually the offset of the first character that could not be parsed,
+    /// or the length of the input with trailing whitespace removed if the rule string
+    /// ends too early. Errors about a number, such as
+    /// [`GenOverflow`](ParseRuleError::GenOverflow), point at its first digit,
+    /// and errors about the data of a MAP string as a whole, such as
+    /// [`InvalidLength`](ParseRuleError::InvalidLength), point at the start of the data.
+    pub offset: usize,
+}
+
+impl ParseError {
+    /// Constructs an error at byte `offset`, unless the error already has its own offset,
+    /// as [`ExtraJunk`](ParseRuleError::ExtraJunk) does.
+    pub(crate) fn at(kind: ParseRuleError, offset: usize) -> Self {
+        let offset = match kind {
+            ParseRuleError::ExtraJunk { offset, .. } => offset,
+            _ => offset,
+        };
+        ParseError { kind, offset }
+    }
+}
+
+/// Errors that can be returned when parsing Golly `.rule` files.
+#[derive(Clone, Debug, PartialEq, Eq, Error, Display)]
+pub enum ParseRuleFileError {
+    /// Missing `@RULE` line with the name of the rule
+    MissingRuleName,
+    /// Missing `{0}` in `@TABLE`
+    MissingField(&'static str),
+    /// Invalid line {0}
+    InvalidLine(usize),
+    /// Invalid number of states on line {0}
+    InvalidStates(usize),
+    /// Unknown neighborhood on line {0}
+    UnknownNeighborhood(usize),
+    /// Unknown symmetries on line {0}
+    UnknownSymmetries(usize),
+    /// Invalid variable definition on line {0}
+    InvalidVariable(usize),
+    /// Invalid transition on line {0}
+    InvalidTransition(usize),
 }
@@ -0,0 +1,1564 @@
+//! Parsing [Golly `.rule` files](http://golly.sourceforge.net/Help/formats.html#rule).
+//!
+//! Only the `@RULE` name and the `@TABLE` section are read.
+//! Other sections, such as `@TREE`, `@COLORS` and `@ICONS`, are skipped.
+//! Both `@TABLE` and `@TREE` sections can be generated from `b` / `s` data,
+//! see [`RuleTable::from_bs`] and [`RuleTree::from_bs`].
+//! Tables for Generations rules can be generated with [`RuleTable::from_bsg`].
+//!
+//! # Examples
+//!
+//! ```
+//! use ca_rules::golly::parse_rule_file;
+//!
+//! let file = parse_rule_file(
+//!     "@RULE Seeds
+//!
+//!      @TABLE
+//!      n_states:2
+//!      neighborhood:Moore
+//!      symmetries:permute
+//!      var a={0,1}
+//!      var b={0,1}
+//!      var c={0,1}
+//!      var d={0,1}
+//!      var e={0,1}
+//!      var f={0,1}
+//!      var g={0,1}
+//!      var h={0,1}
+//!      0,1,1,0,0,0,0,0,0,1
+//!      1,a,b,c,d,e,f,g,h,0",
+//! )
+//! .unwrap();
+//!
+//! assert_eq!(file.name, "Seeds");
+//! let (b, s) = file.table.unwrap().to_bs().unwrap();
+//! assert_eq!(b.len(), 28);
+//! assert!(s.is_empty());
+//! ```
+
+use crate::{ParseRuleError, ParseRuleFileError};
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt::{self, Display, Formatter},
+};
+
+/// The contents of a Golly `.rule` file.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RuleFile {
+    /// The name of the rule, given by the `@RULE` line.
+    pub name: String,
+    /// The `@TABLE` section, if any.
+    pub table: Option<RuleTable>,
+}
+
+impl RuleFile {
+    /// Parses the name of the rule as a rule string with the given parser.
+    ///
+    /// This is useful for files without a `@TABLE` section,
+    /// whose name is itself a rule string that the parsers of this crate understand.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ca_rules::{golly::parse_rule_file, ParseLifeGen};
+    ///
+    /// #[derive(Debug, Eq, PartialEq)]
+    /// struct Rule {
+    ///     b: Vec<u8>,
+    ///     s: Vec<u8>,
+    ///     gen: usize,
+    /// }
+    ///
+    /// impl ParseLifeGen for Rule {
+    ///     fn from_bsg(b: Vec<u8>, s: Vec<u8>, gen: usize) -> Self {
+    ///         Rule { b, s, gen }
+    ///     }
+    /// }
+    ///
+    /// let file = parse_rule_file("@RULE g3b2s").unwrap();
+    /// assert!(file.table.is_none());
+    /// assert_eq!(file.parse_name(Rule::parse_rule).unwrap().gen, 3);
+    /// ```
+    pub fn parse_name<T, F>(&self, parser: F) -> Result<T, ParseRuleError>
+    where
+        F: FnOnce(&str) -> Result<T, ParseRuleError>,
+    {
+        parser(&self.name)
+    }
+}
+
+/// The neighborhood of a rule table.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum TableNeighborhood {
+    /// `neighborhood:Moore`, with neighbors in the order `N, NE, E, SE, S, SW, W, NW`.
+    Moore,
+    /// `neighborhood:vonNeumann`, with neighbors in the order `N, E, S, W`.
+    VonNeumann,
+    /// `neighborhood:hexagonal`, with neighbors in the order `N, E, SE, S, W, NW`.
+    Hexagonal,
+    /// `neighborhood:oneDimensional`, with neighbors in the order `W, E`.
+    OneDimensional,
+}
+
+impl TableNeighborhood {
+    /// The number of neighbors, not counting the center cell.
+    pub fn size(self) -> usize {
+        match self {
+            TableNeighborhood::Moore => 8,
+            TableNeighborhood::VonNeumann => 4,
+            TableNeighborhood::Hexagonal => 6,
+            TableNeighborhood::OneDimensional => 2,
+        }
+    }
+
+    /// The name used in the `neighborhood:` line.
+    pub fn name(self) -> &'static str {
+        match self {
+            TableNeighborhood::Moore => "Moore",
+            TableNeighborhood::VonNeumann => "vonNeumann",
+            TableNeighborhood::Hexagonal => "hexagonal",
+            TableNeighborhood::OneDimensional => "oneDimensional",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        [
+            TableNeighborhood::Moore,
+            TableNeighborhood::VonNeumann,
+            TableNeighborhood::Hexagonal,
+            TableNeighborhood::OneDimensional,
+        ]
+        .into_iter()
+        .find(|neighborhood| neighborhood.name() == name)
+    }
+
+    /// For each neighbor in the order of the table, the corresponding bit
+    /// in the `b` / `s` data of the non-totalistic parsers.
+    fn bits(self) -> Option<&'static [u8]> {
+        match self {
+            TableNeighborhood::Moore => Some(&[6, 5, 3, 0, 1, 2, 4, 7]),
+            TableNeighborhood::Hexagonal => Some(&[4, 2, 0, 1, 3, 5]),
+            TableNeighborhood::VonNeumann => Some(&[3, 1, 0, 2]),
+            TableNeighborhood::OneDimensional => None,
+        }
+    }
+
+    /// The states of the neighbors in a configuration given by `bits`.
+    fn to_neighbors(bits: &[u8], i: usize) -> Vec<u8> {
+        bits.iter().map(|&bit| (i >> bit & 1) as u8).collect()
+    }
+
+    /// The configuration given by the states of the neighbors.
+    fn from_neighbors(bits: &[u8], neighbors: &[u8]) -> usize {
+        bits.iter()
+            .zip(neighbors)
+            .map(|(&bit, &state)| (state as usize) << bit)
+            .sum()
+    }
+}
+
+/// The symmetries of a rule table.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum Symmetries {
+    /// `symmetries:none`
+    None,
+    /// `symmetries:rotate2`, hexagonal only.
+    Rotate2,
+    /// `symmetries:rotate3`, hexagonal only.
+    Rotate3,
+    /// `symmetries:rotate4`, Moore and von Neumann only.
+    Rotate4,
+    /// `symmetries:rotate4reflect`, Moore and von Neumann only.
+    Rotate4Reflect,
+    /// `symmetries:rotate6`, hexagonal only.
+    Rotate6,
+    /// `symmetries:rotate6reflect`, hexagonal only.
+    Rotate6Reflect,
+    /// `symmetries:rotate8`, Moore only.
+    Rotate8,
+    /// `symmetries:rotate8reflect`, Moore only.
+    Rotate8Reflect,
+    /// `symmetries:reflect_horizontal`, Moore and von Neumann only.
+    ReflectHorizontal,
+    /// `symmetries:reflect`, one-dimensional only.
+    Reflect,
+    /// `symmetries:permute`
+    Permute,
+}
+
+impl Symmetries {
+    /// The name used in the `symmetries:` line.
+    pub fn name(self) -> &'static str {
+        match self {
+            Symmetries::None => "none",
+            Symmetries::Rotate2 => "rotate2",
+            Symmetries::Rotate3 => "rotate3",
+            Symmetries::Rotate4 => "rotate4",
+            Symmetries::Rotate4Reflect => "rotate4reflect",
+            Symmetries::Rotate6 => "rotate6",
+            Symmetries::Rotate6Reflect => "rotate6reflect",
+            Symmetries::Rotate8 => "rotate8",
+            Symmetries::Rotate8Reflect => "rotate8reflect",
+            Symmetries::ReflectHorizontal => "reflect_horizontal",
+            Symmetries::Reflect => "reflect",
+            Symmetries::Permute => "permute",
+        }
+    }
+
+    fn from_name(name: &str, neighborhood: TableNeighborhood) -> Option<Self> {
+        use TableNeighborhood::*;
+        let symmetries = [
+            Symmetries::None,
+            Symmetries::Rotate2,
+            Symmetries::Rotate3,
+            Symmetries::Rotate4,
+            Symmetries::Rotate4Reflect,
+            Symmetries::Rotate6,
+            Symmetries::Rotate6Reflect,
+            Symmetries::Rotate8,
+            Symmetries::Rotate8Reflect,
+            Symmetries::ReflectHorizontal,
+            Symmetries::Reflect,
+            Symmetries::Permute,
+        ]
+        .into_iter()
+        .find(|symmetries| symmetries.name() == name)?;
+        let valid = match symmetries {
+            Symmetries::None | Symmetries::Permute => true,
+            Symmetries::Rotate4 | Symmetries::Rotate4Reflect | Symmetries::ReflectHorizontal => {
+                neighborhood == Moore || neighborhood == VonNeumann
+            }
+            Symmetries::Rotate8 | Symmetries::Rotate8Reflect => neighborhood == Moore,
+            Symmetries::Rotate2
+            | Symmetries::Rotate3
+            | Symmetries::Rotate6
+            | Symmetries::Rotate6Reflect => neighborhood == Hexagonal,
+            Symmetries::Reflect => neighborhood == OneDimensional,
+        };
+        if valid {
+            Some(symmetries)
+        } else {
+            None
+        }
+    }
+
+    /// The permutations of the neighbors generated by the symmetries,
+    /// or `None` for `permute`, which allows all of them.
+    fn permutations(self, neighborhood: TableNeighborhood) -> Option<Vec<Vec<usize>>> {
+        let n = neighborhood.size();
+        let (rotations, reflect) = match self {
+            Symmetries::None => (1, false),
+            Symmetries::Rotate2 => (2, false),
+            Symmetries::Rotate3 => (3, false),
+            Symmetries::Rotate4 => (4, false),
+            Symmetries::Rotate4Reflect => (4, true),
+            Symmetries::Rotate6 => (6, false),
+            Symmetries::Rotate6Reflect => (6, true),
+            Symmetries::Rotate8 => (8, false),
+            Symmetries::Rotate8Reflect => (8, true),
+            Symmetries::ReflectHorizontal | Symmetries::Reflect => (1, true),
+            Symmetries::Permute => return None,
+        };
+        // The neighbors of each neighborhood are listed in cyclic order,
+        // so rotations are cyclic shifts, and reflections reverse the order.
+        // Except for the one-dimensional case, the reflection fixes the first neighbor.
+        let step = n / rotations;
+        let mirror = if self == Symmetries::Reflect {
+            n - 1
+        } else {
+            n
+        };
+        let mut permutations = Vec::new();
+        for r in 0..rotations {
+            permutations.push((0..n).map(|i| (i + r * step) % n).collect());
+            if reflect {
+                permutations.push((0..n).map(|i| (mirror + r * step - i) % n).collect());
+            }
+        }
+        Some(permutations)
+    }
+}
+
+/// A variable defined by a `var` line, e.g., `var a={0,1}`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Variable {
+    /// The name of the variable.
+    pub name: String,
+    /// The states that the variable can take.
+    pub states: Vec<u8>,
+}
+
+/// An entry in a transition line.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum TableEntry {
+    /// A single state.
+    State(u8),
+    /// A variable, given by its index in [`RuleTable::variables`].
+    ///
+    /// All occurrences of the same variable in a transition take the same state.
+    Variable(usize),
+}
+
+/// A transition line.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Transition {
+    /// The line number of the transition in the file, starting from 1.
+    pub line: usize,
+    /// The state of the center cell, followed by the states of the neighbors.
+    pub inputs: Vec<TableEntry>,
+    /// The new state of the center cell.
+    pub output: TableEntry,
+}
+
+/// The `@TABLE` section of a Golly `.rule` file.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RuleTable {
+    /// The number of states.
+    pub n_states: usize,
+    /// The neighborhood.
+    pub neighborhood: TableNeighborhood,
+    /// The symmetries applied to the transitions.
+    pub symmetries: Symmetries,
+    /// The variables, in the order of their definitions.
+    pub variables: Vec<Variable>,
+    /// The transitions, in the order of their definitions.
+    pub transitions: Vec<Transition>,
+}
+
+impl RuleTable {
+    /// The new state of a cell, given its current state and the states of its neighbors,
+    /// in the order described in [`TableNeighborhood`].
+    ///
+    /// The first matching transition is used. If no transition matches, the state is unchanged.
+    /// The state is also unchanged if some state is not less than the number of states,
+    /// since no transition can match it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of neighbors does not match the neighborhood.
+    pub fn next_state(&self, center: u8, neighbors: &[u8]) -> u8 {
+        assert_eq!(neighbors.len(), self.neighborhood.size());
+        if neighbors
+            .iter()
+            .any(|&state| state as usize >= self.n_states)
+        {
+            return center;
+        }
+        let permutations = self.symmetries.permutations(self.neighborhood);
+        for transition in &self.transitions {
+            let mut bindings = vec![None; self.variables.len()];
+            if !self.match_entry(transition.inputs[0], center, &mut bindings) {
+                continue;
+            }
+            let matched = match &permutations {
+                Some(permutations) => permutations.iter().find_map(|permutation| {
+                    let mut bindings = bindings.clone();
+                    transition.inputs[1..]
+                        .iter()
+                        .zip(permutation)
+                        .all(|(&entry, &i)| self.match_entry(entry, neighbors[i], &mut bindings))
+                        .then_some(bindings)
+                }),
+                None => {
+                    let mut counts = vec![0; self.n_states];
+                    for &state in neighbors {
+                        counts[state as usize] += 1;
+                    }
+                    self.match_permuted(&transition.inputs[1..], &mut counts, &mut bindings)
+                        .then_some(bindings)
+                }
+            };
+            if let Some(bindings) = matched {
+                return match transition.output {
+                    TableEntry::State(state) => state,
+                    TableEntry::Variable(v) => bindings[v].unwrap(),
+                };
+            }
+        }
+        center
+    }
+
+    /// Converts a two-state table with Moore, hexagonal or von Neumann neighborhood
+    /// into `b` / `s` data.
+    ///
+    /// The neighborhoods are encoded in the same way as in [`ParseNtLife`](crate::ParseNtLife),
+    /// [`ParseNtHex`](crate::ParseNtHex) and [`ParseNtNeumann`](crate::ParseNtNeumann), respectively,
+    /// so the result can be passed to their `from_bs`.
+    ///
+    /// Returns `None` for other tables.
+    pub fn to_bs(&self) -> Option<(Vec<u8>, Vec<u8>)> {
+        let bits = self.neighborhood.bits()?;
+        if self.n_states != 2 {
+            return None;
+        }
+        let mut b = Vec::new();
+        let mut s = Vec::new();
+        for i in 0..1 << bits.len() {
+            let neighbors = TableNeighborhood::to_neighbors(bits, i);
+            if self.next_state(0, &neighbors) == 1 {
+                b.push(i as u8);
+            }
+            if self.next_state(1, &neighbors) == 1 {
+                s.push(i as u8);
+            }
+        }
+        Some((b, s))
+    }
+
+    /// Constructs a two-state table with Moore, hexagonal or von Neumann neighborhood
+    /// from `b` / `s` data, encoded as in [`to_bs`](RuleTable::to_bs).
+    ///
+    /// When the rule is isotropic, the table uses the `rotate4reflect` or `rotate6reflect`
+    /// symmetries and only lists one transition for each class of configurations.
+    /// Transitions that only differ in one neighbor are merged using variables.
+    ///
+    /// Returns `None` for the one-dimensional neighborhood,
+    /// or if some value in `b` or `s` is out of range for the neighborhood.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ca_rules::golly::{RuleFile, RuleTable, TableNeighborhood};
+    ///
+    /// let b = [0b0111, 0b1011, 0b1101, 0b1110];
+    /// let table = RuleTable::from_bs(TableNeighborhood::VonNeumann, &b, &[]).unwrap();
+    /// let file = RuleFile {
+    ///     name: "Test".to_string(),
+    ///     table: Some(table),
+    /// };
+    ///
+    /// assert_eq!(
+    ///     file.to_string(),
+    ///     "@RULE Test\n\
+    ///      \n\
+    ///      @TABLE\n\
+    ///      n_states:2\n\
+    ///      neighborhood:vonNeumann\n\
+    ///      symmetries:rotate4reflect\n\
+    ///      var a={0,1}\n\
+    ///      var b={0,1}\n\
+    ///      var c={0,1}\n\
+    ///      var d={0,1}\n\
+    ///      0,0,1,1,1,1\n\
+    ///      1,a,b,c,d,0\n"
+    /// );
+    /// ```
+    pub fn from_bs(neighborhood: TableNeighborhood, b: &[u8], s: &[u8]) -> Option<Self> {
+        let bits = neighborhood.bits()?;
+        let size = 1 << bits.len();
+        let symmetries = match neighborhood {
+            TableNeighborhood::Hexagonal => Symmetries::Rotate6Reflect,
+            _ => Symmetries::Rotate4Reflect,
+        };
+        let permutations = symmetries.permutations(neighborhood).unwrap();
+        let orbit = |i: usize| {
+            let neighbors = TableNeighborhood::to_neighbors(bits, i);
+            permutations.iter().map(move |permutation| {
+                let permuted: Vec<u8> = permutation.iter().map(|&j| neighbors[j]).collect();
+                TableNeighborhood::from_neighbors(bits, &permuted)
+            })
+        };
+
+        let mut in_b = vec![false; size];
+        let mut in_s = vec![false; size];
+        for &i in b {
+            *in_b.get_mut(i as usize)? = true;
+        }
+        for &i in s {
+            *in_s.get_mut(i as usize)? = true;
+        }
+        let isotropic = (0..size)
+            .all(|i| orbit(i).all(|j| in_b[j] == in_b[i]) && orbit(i).all(|j| in_s[j] == in_s[i]));
+
+        // The configurations to list, as vectors of states of the neighbors.
+        let listed = |set: &[bool]| -> Vec<Vec<u8>> {
+            (0..size)
+                .filter(|&i| set[i] && (!isotropic || orbit(i).all(|j| j >= i)))
+                .map(|i| TableNeighborhood::to_neighbors(bits, i))
+                .collect()
+        };
+        let mut lines = Vec::new();
+        for neighbors in Self::merge(listed(&in_b)) {
+            lines.push((0, neighbors, 1));
+        }
+        // Cells that are not explicitly updated stay unchanged,
+        // so survivals only need to be listed when some cells die.
+        if in_s.contains(&false) {
+            for neighbors in Self::merge(listed(&in_s)) {
+                lines.push((1, neighbors, 1));
+            }
+            lines.push((1, vec![None; bits.len()], 0));
+        }
+
+        let wildcard = lines
+            .iter()
+            .any(|(_, neighbors, _)| neighbors.contains(&None));
+        let variables = if wildcard {
+            (b'a'..)
+                .take(bits.len())
+                .map(|c| Variable {
+                    name: (c as char).to_string(),
+                    states: vec![0, 1],
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+        let transitions =
+            lines
+                .into_iter()
+                .map(|(center, neighbors, output)| {
+                    let mut inputs = vec![TableEntry::State(center)];
+                    inputs.extend(neighbors.into_iter().enumerate().map(|(k, state)| {
+                        state.map_or(TableEntry::Variable(k), TableEntry::State)
+                    }));
+                    Transition {
+                        line: 0,
+                        inputs,
+                        output: TableEntry::State(output),
+                    }
+                })
+                .collect();
+
+        Some(RuleTable {
+            n_states: 2,
+            neighborhood,
+            symmetries: if isotropic {
+                symmetries
+            } else {
+                Symmetries::None
+            },
+            variables,
+            transitions,
+        })
+    }
+
+    /// Constructs a table for a [Generations](http://www.conwaylife.com/wiki/Generations) rule
+    /// with Moore, hexagonal or von Neumann neighborhood from `b` / `s` data
+    /// and the number of states, as given to the `from_bsg` methods of the parsers.
+    ///
+    /// State `1` is alive, and states from `2` up are dying. Only live neighbors are counted.
+    /// The transitions are those of [`from_bs`](RuleTable::from_bs), where a cell that does
+    /// not survive starts dying, followed by one transition for each dying state.
+    ///
+    /// Returns `None` when [`from_bs`](RuleTable::from_bs) does,
+    /// or if `gen` is less than `2` or greater than `256`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ca_rules::golly::{RuleTable, TableNeighborhood};
+    ///
+    /// // Brian's Brain, `B2/S/C3`.
+    /// let b: Vec<u8> = (0..=0xff).filter(|i: &u8| i.count_ones() == 2).collect();
+    /// let table = RuleTable::from_bsg(TableNeighborhood::Moore, &b, &[], 3).unwrap();
+    ///
+    /// assert_eq!(table.n_states, 3);
+    /// assert_eq!(table.next_state(0, &[1, 2, 1, 0, 0, 0, 2, 0]), 1);
+    /// assert_eq!(table.next_state(1, &[1, 1, 1, 0, 0, 0, 0, 0]), 2);
+    /// assert_eq!(table.next_state(2, &[1, 1, 0, 0, 0, 0, 0, 0]), 0);
+    /// ```
+    pub fn from_bsg(
+        neighborhood: TableNeighborhood,
+        b: &[u8],
+        s: &[u8],
+        gen: usize,
+    ) -> Option<Self> {
+        let mut table = Self::from_bs(neighborhood, b, s)?;
+        if gen == 2 {
+            return Some(table);
+        } else if !(2..=256).contains(&gen) {
+            return None;
+        }
+        let n = neighborhood.size();
+        table.n_states = gen;
+        // Variables `a`, `b`, ... stand for any state, and `na`, `nb`, ... for any state
+        // but alive, one for each neighbor, so that they can be bound independently.
+        let names = (b'a'..).take(n).map(|c| (c as char).to_string());
+        table.variables = names
+            .clone()
+            .map(|name| Variable {
+                name,
+                states: (0..gen).map(|state| state as u8).collect(),
+            })
+            .chain(names.map(|name| {
+                Variable {
+                    name: format!("n{}", name),
+                    states: (0..gen)
+                        .filter(|&state| state != 1)
+                        .map(|state| state as u8)
+                        .collect(),
+                }
+            }))
+            .collect();
+        for transition in &mut table.transitions {
+            for (k, entry) in transition.inputs.iter_mut().enumerate().skip(1) {
+                if *entry == TableEntry::State(0) {
+                    *entry = TableEntry::Variable(n + k - 1);
+                }
+            }
+            if transition.inputs[0] == TableEntry::State(1)
+                && transition.output == TableEntry::State(0)
+            {
+                transition.output = TableEntry::State(2);
+            }
+        }
+        for state in 2..gen {
+            let mut inputs = vec![TableEntry::State(state as u8)];
+            inputs.extend((0..n).map(TableEntry::Variable));
+            table.transitions.push(Transition {
+                line: 0,
+                inputs,
+                output: TableEntry::State(((state + 1) % gen) as u8),
+            });
+        }
+        Some(table)
+    }
+
+    /// Repeatedly merges pairs of configurations that only differ in one neighbor,
+    /// replacing that neighbor by `None`.
+    fn merge(configurations: Vec<Vec<u8>>) -> Vec<Vec<Option<u8>>> {
+        let mut configurations: BTreeSet<Vec<Option<u8>>> = configurations
+            .into_iter()
+            .map(|neighbors| neighbors.into_iter().map(Some).collect())
+            .collect();
+        let n = configurations.first().map_or(0, Vec::len);
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for k in 0..n {
+                let pairs: Vec<_> = configurations
+                    .iter()
+                    .filter(|config| config[k] == Some(0))
+                    .filter_map(|config| {
+                        let mut other = config.clone();
+                        other[k] = Some(1);
+                        configurations
+                            .contains(&other)
+                            .then(|| (config.clone(), other))
+                    })
+                    .collect();
+                for (config, other) in pairs {
+                    configurations.remove(&config);
+                    configurations.remove(&other);
+                    let mut merged = config;
+                    merged[k] = None;
+                    configurations.insert(merged);
+                    changed = true;
+                }
+            }
+        }
+        configurations.into_iter().collect()
+    }
+
+    fn match_entry(&self, entry: TableEntry, state: u8, bindings: &mut [Option<u8>]) -> bool {
+        match entry {
+            TableEntry::State(s) => s == state,
+            TableEntry::Variable(v) => match bindings[v] {
+                Some(s) => s == state,
+                None if self.variables[v].states.contains(&state) => {
+                    bindings[v] = Some(state);
+                    true
+                }
+                None => false,
+            },
+        }
+    }
+
+    /// Whether the entries match some permutation of the neighbors,
+    /// whose states are given by their counts.
+    fn match_permuted(
+        &self,
+        entries: &[TableEntry],
+        counts: &mut [usize],
+        bindings: &mut [Option<u8>],
+    ) -> bool {
+        let (&entry, rest) = match entries.split_first() {
+            Some(split) => split,
+            None => return true,
+        };
+        for state in 0..self.n_states {
+            if counts[state] == 0 {
+                continue;
+            }
+            let mut new_bindings = bindings.to_vec();
+            if self.match_entry(entry, state as u8, &mut new_bindings) {
+                counts[state] -= 1;
+                let matched = self.match_permuted(rest, counts, &mut new_bindings);
+                counts[state] += 1;
+                if matched {
+                    bindings.copy_from_slice(&new_bindings);
+                    return true;
+                }
+            }
+        }
+        false
+    }
+}
+
+impl Display for RuleTable {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        writeln!(f, "@TABLE")?;
+        writeln!(f, "n_states:{}", self.n_states)?;
+        writeln!(f, "neighborhood:{}", self.neighborhood.name())?;
+        writeln!(f, "symmetries:{}", self.symmetries.name())?;
+        for variable in &self.variables {
+            let states: Vec<String> = variable.states.iter().map(u8::to_string).collect();
+            writeln!(f, "var {}={{{}}}", variable.name, states.join(","))?;
+        }
+        for transition in &self.transitions {
+            let line: Vec<String> = transition
+                .inputs
+                .iter()
+                .chain([&transition.output])
+                .map(|entry| match *entry {
+                    TableEntry::State(state) => state.to_string(),
+                    TableEntry::Variable(v) => self.variables[v].name.clone(),
+                })
+                .collect();
+            writeln!(f, "{}", line.join(","))?;
+        }
+        Ok(())
+    }
+}
+
+/// A node of a rule tree.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct TreeNode {
+    /// The level of the node, starting from 1.
+    pub level: usize,
+    /// The children of the node, one for each state of the cell at this level.
+    ///
+    /// The children of a node at level 1 are new states of the center cell;
+    /// those of other nodes are indices of nodes at the level below.
+    pub children: Vec<usize>,
+}
+
+/// The `@TREE` section of a Golly `.rule` file.
+///
+/// The root is the last node. As in Golly, it branches on the state of the first neighbor
+/// in the order `NW, NE, SW, SE, N, W, E, S` for the Moore neighborhood,
+/// or `N, W, E, S` for the von Neumann neighborhood. Each level below branches
+/// on the next neighbor, and the nodes at level 1 branch on the center cell.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RuleTree {
+    /// The number of states.
+    pub n_states: usize,
+    /// The number of neighbors, either 4 or 8.
+    pub n_neighbors: usize,
+    /// The nodes, each appearing after all its children.
+    pub nodes: Vec<TreeNode>,
+}
+
+impl RuleTree {
+    /// The new state of a cell, given its current state and the states of its neighbors,
+    /// in the order described in [`RuleTree`].
+    ///
+    /// As in [`RuleTable::next_state`], the state is unchanged if some state
+    /// is not less than the number of states.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of neighbors does not match the tree.
+    pub fn next_state(&self, center: u8, neighbors: &[u8]) -> u8 {
+        assert_eq!(neighbors.len(), self.n_neighbors);
+        if neighbors
+            .iter()
+            .chain([center].iter())
+            .any(|&state| state as usize >= self.n_states)
+        {
+            return center;
+        }
+        let mut node = &self.nodes[self.nodes.len() - 1];
+        for &state in neighbors.iter().chain([center].iter()) {
+            let child = node.children[state as usize];
+            if node.level == 1 {
+                return child as u8;
+            }
+            node = &self.nodes[child];
+        }
+        unreachable!()
+    }
+
+    /// Builds the rule tree of a two-state rule
+    /// from the `b` / `s` data of the non-totalistic parsers.
+    ///
+    /// Identical subtrees are shared, so the result is the smallest tree
+    /// with this order of neighbors.
+    ///
+    /// A hexagonal rule gives a tree for the Moore neighborhood that ignores
+    /// the `NE` and `SW` neighbors, since Golly's rule trees only support
+    /// the Moore and von Neumann neighborhoods.
+    /// Returns `None` for one-dimensional rules,
+    /// or if some value in `b` or `s` is out of range for the neighborhood.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ca_rules::golly::{RuleTree, TableNeighborhood};
+    ///
+    /// // B1/S, with the von Neumann neighborhood.
+    /// let b = [0b0001, 0b0010, 0b0100, 0b1000];
+    /// let tree = RuleTree::from_bs(TableNeighborhood::VonNeumann, &b, &[]).unwrap();
+    ///
+    /// assert_eq!(tree.next_state(0, &[0, 1, 0, 0]), 1);
+    /// assert_eq!(tree.next_state(0, &[0, 1, 1, 0]), 0);
+    /// assert_eq!(tree.next_state(1, &[0, 1, 0, 0]), 0);
+    /// assert_eq!(
+    ///     tree.to_string(),
+    ///     "@TREE\n\
+    ///      num_states=2\n\
+    ///      num_neighbors=4\n\
+    ///      num_nodes=11\n\
+    ///      1 0 0\n\
+    ///      1 1 0\n\
+    ///      2 0 1\n\
+    ///      2 1 0\n\
+    ///      3 2 3\n\
+    ///      2 0 0\n\
+    ///      3 3 5\n\
+    ///      4 4 6\n\
+    ///      3 5 5\n\
+    ///      4 6 8\n\
+    ///      5 7 9\n"
+    /// );
+    /// ```
+    pub fn from_bs(neighborhood: TableNeighborhood, b: &[u8], s: &[u8]) -> Option<Self> {
+        let bits: &[Option<u8>] = match neighborhood {
+            TableNeighborhood::Moore => &[
+                Some(7),
+                Some(5),
+                Some(2),
+                Some(0),
+                Some(6),
+                Some(4),
+                Some(3),
+                Some(1),
+            ],
+            TableNeighborhood::Hexagonal => &[
+                Some(5),
+                None,
+                None,
+                Some(0),
+                Some(4),
+                Some(3),
+                Some(2),
+                Some(1),
+            ],
+            TableNeighborhood::VonNeumann => &[Some(3), Some(2), Some(1), Some(0)],
+            TableNeighborhood::OneDimensional => return None,
+        };
+        let size = 1 << neighborhood.size();
+        let mut builder = TreeBuilder {
+            bits,
+            in_b: vec![false; size],
+            in_s: vec![false; size],
+            nodes: Vec::new(),
+            indices: HashMap::new(),
+        };
+        for &i in b {
+            *builder.in_b.get_mut(i as usize)? = true;
+        }
+        for &i in s {
+            *builder.in_s.get_mut(i as usize)? = true;
+        }
+        builder.build(bits.len() + 1, 0);
+        Some(RuleTree {
+            n_states: 2,
+            n_neighbors: bits.len(),
+            nodes: builder.nodes,
+        })
+    }
+}
+
+/// A helper struct to build rule trees.
+struct TreeBuilder {
+    bits: &'static [Option<u8>],
+    in_b: Vec<bool>,
+    in_s: Vec<bool>,
+    nodes: Vec<TreeNode>,
+    indices: HashMap<TreeNode, usize>,
+}
+
+impl TreeBuilder {
+    /// Builds the node at `level`, given the configuration of the neighbors
+    /// at the levels above, and returns its index.
+    fn build(&mut self, level: usize, config: usize) -> usize {
+        let mut children = Vec::with_capacity(2);
+        for state in 0..2 {
+            children.push(if level == 1 {
+                if state == 0 {
+                    self.in_b[config] as usize
+                } else {
+                    self.in_s[config] as usize
+                }
+            } else {
+                let config = match self.bits[self.bits.len() + 1 - level] {
+                    Some(bit) => config | state << bit,
+                    None => config,
+                };
+                self.build(level - 1, config)
+            });
+        }
+        let node = TreeNode { level, children };
+        if let Some(&index) = self.indices.get(&node) {
+            return index;
+        }
+        self.nodes.push(node.clone());
+        self.indices.insert(node, self.nodes.len() - 1);
+        self.nodes.len() - 1
+    }
+}
+
+impl Display for RuleTree {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        writeln!(f, "@TREE")?;
+        writeln!(f, "num_states={}", self.n_states)?;
+        writeln!(f, "num_neighbors={}", self.n_neighbors)?;
+        writeln!(f, "num_nodes={}", self.nodes.len())?;
+        for node in &self.nodes {
+            let children: Vec<String> = node.children.iter().map(usize::to_string).collect();
+            writeln!(f, "{} {}", node.level, children.join(" "))?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for RuleFile {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        writeln!(f, "@RULE {}", self.name)?;
+        if let Some(table) = &self.table {
+            writeln!(f)?;
+            write!(f, "{}", table)?;
+        }
+        Ok(())
+    }
+}
+
+/// A parser for the `@TABLE` section.
+#[derive(Default)]
+struct TableParser {
+    n_states: Option<usize>,
+    neighborhood: Option<TableNeighborhood>,
+    symmetries: Option<Symmetries>,
+    variables: Vec<Variable>,
+    transitions: Vec<Transition>,
+}
+
+impl TableParser {
+    fn parse_line(&mut self, line: &str, n: usize) -> Result<(), ParseRuleFileError> {
+        if let Some((key, value)) = line.split_once(':') {
+            let value = value.trim();
+            match key.trim() {
+                "n_states" => {
+                    let n_states = value
+                        .parse()
+                        .ok()
+                        .filter(|n_states| (2..=256).contains(n_states))
+                        .ok_or(ParseRuleFileError::InvalidStates(n))?;
+                    self.n_states = Some(n_states);
+                }
+                "neighborhood" => {
+                    let neighborhood = TableNeighborhood::from_name(value)
+                        .ok_or(ParseRuleFileError::UnknownNeighborhood(n))?;
+                    self.neighborhood = Some(neighborhood);
+                }
+                "symmetries" => {
+                    let neighborhood = self
+                        .neighborhood
+                        .ok_or(ParseRuleFileError::MissingField("neighborhood"))?;
+                    let symmetries = Symmetries::from_name(value, neighborhood)
+                        .ok_or(ParseRuleFileError::UnknownSymmetries(n))?;
+                    self.symmetries = Some(symmetries);
+                }
+                _ => return Err(ParseRuleFileError::InvalidLine(n)),
+            }
+        } else if let Some(definition) = line.strip_prefix("var ") {
+            let (name, states) = definition
+                .split_once('=')
+                .ok_or(ParseRuleFileError::InvalidVariable(n))?;
+            let name = name.trim();
+            let states = states.trim();
+            let states = states
+                .strip_prefix('{')
+                .and_then(|states| states.strip_suffix('}'))
+                .unwrap_or(states);
+            if name.is_empty() || name.parse::<u8>().is_ok() {
+                return Err(ParseRuleFileError::InvalidVariable(n));
+            }
+            let mut list = Vec::new();
+            for item in states.split(',') {
+                let entry = self.parse_entry(item.trim(), n).map_err(|e| match e {
+                    ParseRuleFileError::InvalidTransition(n) => {
+                        ParseRuleFileError::InvalidVariable(n)
+                    }
+                    e => e,
+                })?;
+                match entry {
+                    TableEntry::State(state) => list.push(state),
+                    TableEntry::Variable(v) => list.extend_from_slice(&self.variables[v].states),
+                }
+            }
+            list.sort_unstable();
+            list.dedup();
+            self.variables.push(Variable {
+                name: name.to_owned(),
+                states: list,
+            });
+        } else {
+            let size = self
+                .neighborhood
+                .ok_or(ParseRuleFileError::MissingField("neighborhood"))?
+                .size();
+            self.symmetries
+                .ok_or(ParseRuleFileError::MissingField("symmetries"))?;
+            let n_states = self
+                .n_states
+                .ok_or(ParseRuleFileError::MissingField("n_states"))?;
+            let mut entries = if line.contains(',') {
+                line.split(',')
+                    .map(|item| self.parse_entry(item.trim(), n))
+                    .collect::<Result<Vec<_>, _>>()?
+            } else if n_states <= 10 {
+                line.chars()
+                    .filter(|c| !c.is_ascii_whitespace())
+                    .map(|c| self.parse_entry(c.encode_utf8(&mut [0; 4]), n))
+                    .collect::<Result<Vec<_>, _>>()?
+            } else {
+                return Err(ParseRuleFileError::InvalidTransition(n));
+            };
+            if entries.len() != size + 2 {
+                return Err(ParseRuleFileError::InvalidTransition(n));
+            }
+            let mut output = entries.pop().unwrap();
+            if let TableEntry::Variable(v) = output {
+                // An output variable that does not occur in the inputs must have a single state.
+                if !entries.contains(&output) {
+                    match self.variables[v].states[..] {
+                        [state] => output = TableEntry::State(state),
+                        _ => return Err(ParseRuleFileError::InvalidTransition(n)),
+                    }
+                }
+            }
+            self.transitions.push(Transition {
+                line: n,
+                inputs: entries,
+                output,
+            });
+        }
+        Ok(())
+    }
+
+    fn parse_entry(&self, item: &str, n: usize) -> Result<TableEntry, ParseRuleFileError> {
+        let n_states = self
+            .n_states
+            .ok_or(ParseRuleFileError::MissingField("n_states"))?;
+        if let Ok(state) = item.parse::<usize>() {
+            if state < n_states {
+                Ok(TableEntry::State(state as u8))
+            } else {
+                Err(ParseRuleFileError::InvalidTransition(n))
+            }
+        } else {
+            self.variables
+                .iter()
+                .rposition(|v| v.name == item)
+                .map(TableEntry::Variable)
+                .ok_or(ParseRuleFileError::InvalidTransition(n))
+        }
+    }
+
+    fn finish(self) -> Result<RuleTable, ParseRuleFileError> {
+        Ok(RuleTable {
+            n_states: self
+                .n_states
+                .ok_or(ParseRuleFileError::MissingField("n_states"))?,
+            neighborhood: self
+                .neighborhood
+                .ok_or(ParseRuleFileError::MissingField("neighborhood"))?,
+            symmetries: self
+                .symmetries
+                .ok_or(ParseRuleFileError::MissingField("symmetries"))?,
+            variables: self.variables,
+            transitions: self.transitions,
+        })
+    }
+}
+
+/// Parses the contents of a Golly `.rule` file.
+///
+/// Both `\n` and `\r\n` line endings are accepted.
+/// Errors in the `@TABLE` section report the line number, starting from 1.
+pub fn parse_rule_file(input: &str) -> Result<RuleFile, ParseRuleFileError> {
+    let mut name = None;
+    let mut table: Option<TableParser> = None;
+    let mut in_table = false;
+
+    for (i, line) in input.lines().enumerate() {
+        let n = i + 1;
+        let line = line.split('#').next().unwrap().trim();
+        if let Some(section) = line.strip_prefix('@') {
+            let mut words = section.split_whitespace();
+            in_table = false;
+            match words.next() {
+                Some("RULE") if name.is_none() => {
+                    name = Some(
+                        words
+                            .next()
+                            .ok_or(ParseRuleFileError::MissingRuleName)?
+                            .to_owned(),
+                    );
+                }
+                Some("TABLE") if name.is_some() && table.is_none() => {
+                    in_table = true;
+                    table = Some(TableParser::default());
+                }
+                Some("TABLE") if name.is_none() => return Err(ParseRuleFileError::MissingRuleName),
+                Some("RULE") | Some("TABLE") => return Err(ParseRuleFileError::InvalidLine(n)),
+                _ => (),
+            }
+        } else if name.is_none() && !line.is_empty() {
+            return Err(ParseRuleFileError::MissingRuleName);
+        } else if in_table && !line.is_empty() {
+            table.as_mut().unwrap().parse_line(line, n)?;
+        }
+    }
+
+    Ok(RuleFile {
+        name: name.ok_or(ParseRuleFileError::MissingRuleName)?,
+        table: table.map(TableParser::finish).transpose()?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ParseNtHex, ParseNtLife};
+
+    #[derive(Debug, Eq, PartialEq)]
+    struct Rule {
+        b: Vec<u8>,
+        s: Vec<u8>,
+    }
+
+    impl ParseNtLife for Rule {
+        fn from_bs(b: Vec<u8>, s: Vec<u8>) -> Self {
+            Rule { b, s }
+        }
+    }
+
+    impl ParseNtHex for Rule {
+        fn from_bs(b: Vec<u8>, s: Vec<u8>) -> Self {
+            Rule { b, s }
+        }
+    }
+
+    const LIFE: &str = "@RULE LifeTable
+# Conway's Game of Life
+
+@TABLE
+n_states:2
+neighborhood:Moore
+symmetries:permute
+var a={0,1}
+var b={a}
+var c={0,1}
+var d={0,1}
+var e={0,1}
+var f={0,1}
+var g={0,1}
+var h={0,1}
+0,1,1,1,0,0,0,0,0,1
+1,1,1,0,0,0,0,0,0,1
+111100000 1   # commas are optional
+1,a,b,c,d,e,f,g,h,0
+
+@COLORS
+0 0 0 0
+1 255 255 255
+";
+
+    #[test]
+    fn life() -> Result<(), ParseRuleError> {
+        let file = parse_rule_file(LIFE).unwrap();
+        assert_eq!(file.name, "LifeTable");
+        let table = file.table.unwrap();
+        assert_eq!(table.n_states, 2);
+        assert_eq!(table.neighborhood, TableNeighborhood::Moore);
+        assert_eq!(table.symmetries, Symmetries::Permute);
+        assert_eq!(table.variables[1].states, vec![0, 1]);
+        assert_eq!(table.transitions.len(), 4);
+        assert_eq!(table.transitions[2].line, 18);
+        let (b, s) = table.to_bs().unwrap();
+        assert_eq!(Rule { b, s }, <Rule as ParseNtLife>::parse_rule("B3/S23")?);
+        // A state out of range leaves the cell unchanged, as with the other symmetries.
+        assert_eq!(table.next_state(1, &[2, 1, 0, 0, 0, 0, 0, 0]), 1);
+        assert_eq!(table.next_state(0, &[2, 1, 1, 1, 0, 0, 0, 0]), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn name_as_rule() -> Result<(), ParseRuleError> {
+        let file = parse_rule_file("@RULE B36/S23\r\n\r\n@COLORS\r\n0 0 0 0\r\n").unwrap();
+        assert_eq!(file.name, "B36/S23");
+        assert!(file.table.is_none());
+        assert_eq!(
+            file.parse_name(<Rule as ParseNtLife>::parse_rule)?,
+            <Rule as ParseNtLife>::parse_rule("B36/S23")?
+        );
+        let file = parse_rule_file("@RULE LifeTable").unwrap();
+        assert_eq!(
+            file.parse_name(<Rule as ParseNtLife>::parse_rule).err(),
+            Some(ParseRuleError::Missing('/'))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn symmetries() -> Result<(), ParseRuleError> {
+        let file = parse_rule_file(
+            "@RULE Test\r\n\
+             @TABLE\r\n\
+             n_states:2\r\n\
+             neighborhood:Moore\r\n\
+             symmetries:rotate4reflect\r\n\
+             0,1,1,0,0,0,0,0,0,1\r\n",
+        )
+        .unwrap();
+        let (b, s) = file.table.unwrap().to_bs().unwrap();
+        assert_eq!(
+            Rule { b, s },
+            <Rule as ParseNtLife>::parse_rule("B2a/S012345678")?
+        );
+
+        let file = parse_rule_file(
+            "@RULE Test
+             @TABLE
+             n_states:2
+             neighborhood:hexagonal
+             symmetries:rotate6
+             0,1,0,1,0,0,0,1",
+        )
+        .unwrap();
+        let (b, s) = file.table.unwrap().to_bs().unwrap();
+        assert_eq!(
+            Rule { b, s },
+            <Rule as ParseNtHex>::parse_rule("B2m/S0123456H")?
+        );
+
+        let file = parse_rule_file(
+            "@RULE Test
+             @TABLE
+             n_states:2
+             neighborhood:vonNeumann
+             symmetries:none
+             var a={0,1}
+             0,1,a,a,0,1
+             1,a,a,a,a,a",
+        )
+        .unwrap();
+        let table = file.table.unwrap();
+        assert_eq!(table.next_state(0, &[1, 1, 1, 0]), 1);
+        assert_eq!(table.next_state(0, &[1, 0, 1, 0]), 0);
+        assert_eq!(table.next_state(1, &[1, 1, 1, 1]), 1);
+        assert_eq!(table.next_state(1, &[0, 1, 0, 1]), 1);
+        let (b, s) = table.to_bs().unwrap();
+        assert_eq!(b, vec![0b1000, 0b1011]);
+        assert_eq!(s.len(), 15);
+        assert!(!s.contains(&0));
+        Ok(())
+    }
+
+    #[test]
+    fn multistate() {
+        let file = parse_rule_file(
+            "@RULE Wire
+             @TABLE
+             n_states:4
+             neighborhood:oneDimensional
+             symmetries:reflect
+             var a={0,1,2,3}
+             1,2,a,3
+             3,a,a,0",
+        )
+        .unwrap();
+        let table = file.table.unwrap();
+        assert_eq!(table.next_state(1, &[0, 2]), 3);
+        assert_eq!(table.next_state(1, &[0, 0]), 1);
+        assert_eq!(table.next_state(3, &[2, 2]), 0);
+        assert_eq!(table.next_state(3, &[1, 2]), 3);
+        assert_eq!(table.next_state(1, &[0, 4]), 1);
+        assert_eq!(table.to_bs(), None);
+    }
+
+    #[test]
+    fn round_trip() -> Result<(), ParseRuleError> {
+        let cases: [(TableNeighborhood, Rule, Symmetries); 4] = [
+            (
+                TableNeighborhood::Moore,
+                <Rule as ParseNtLife>::parse_rule("B3/S23")?,
+                Symmetries::Rotate4Reflect,
+            ),
+            (
+                TableNeighborhood::Moore,
+                Rule {
+                    b: vec![0x01, 0x03, 0x07],
+                    s: (0..=0xff).collect(),
+                },
+                Symmetries::None,
+            ),
+            (
+                TableNeighborhood::Hexagonal,
+                <Rule as ParseNtHex>::parse_rule("B2o3-o4m/S12m3o4m5H")?,
+                Symmetries::Rotate6Reflect,
+            ),
+            (
+                TableNeighborhood::VonNeumann,
+                Rule {
+                    b: vec![0x01, 0x0e],
+                    s: vec![0x00, 0x0f],
+                },
+                Symmetries::None,
+            ),
+        ];
+        for (neighborhood, rule, symmetries) in cases {
+            let table = RuleTable::from_bs(neighborhood, &rule.b, &rule.s).unwrap();
+            assert_eq!(table.symmetries, symmetries);
+            let file = RuleFile {
+                name: "Test".to_string(),
+                table: Some(table),
+            };
+            let parsed = parse_rule_file(&file.to_string()).unwrap();
+            assert_eq!(parsed.name, "Test");
+            let (b, s) = parsed.table.unwrap().to_bs().unwrap();
+            assert_eq!(Rule { b, s }, rule);
+        }
+
+        let table = RuleTable::from_bs(TableNeighborhood::Moore, &[], &[0x00, 0x01]).unwrap();
+        assert_eq!(table.symmetries, Symmetries::None);
+        assert_eq!(table.transitions.len(), 2);
+        assert_eq!(
+            RuleTable::from_bs(TableNeighborhood::OneDimensional, &[], &[]),
+            None
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn generations() -> Result<(), ParseRuleError> {
+        let brain = <Rule as ParseNtLife>::parse_rule("B2/S")?;
+        let cases = [
+            (TableNeighborhood::Moore, brain, 3),
+            (
+                TableNeighborhood::VonNeumann,
+                Rule {
+                    b: vec![0x01, 0x0e],
+                    s: vec![0x00, 0x03],
+                },
+                5,
+            ),
+        ];
+        for (neighborhood, rule, gen) in cases {
+            let table = RuleTable::from_bsg(neighborhood, &rule.b, &rule.s, gen).unwrap();
+            let parsed = parse_rule_file(&format!("@RULE Test\n{}", table))
+                .unwrap()
+                .table
+                .unwrap();
+            assert_eq!(parsed.variables, table.variables);
+            assert_eq!(parsed.transitions.len(), table.transitions.len());
+            let bits = neighborhood.bits().unwrap();
+            let n = neighborhood.size();
+            for i in 0..gen.pow(n as u32) {
+                let neighbors: Vec<u8> = (0..n)
+                    .map(|k| (i / gen.pow(k as u32) % gen) as u8)
+                    .collect();
+                let alive: Vec<u8> = neighbors.iter().map(|&state| (state == 1) as u8).collect();
+                let config = TableNeighborhood::from_neighbors(bits, &alive) as u8;
+                let born = rule.b.contains(&config) as u8;
+                assert_eq!(table.next_state(0, &neighbors), born);
+                let survived = if rule.s.contains(&config) { 1 } else { 2 };
+                assert_eq!(table.next_state(1, &neighbors), survived);
+                for state in 2..gen {
+                    assert_eq!(
+                        table.next_state(state as u8, &neighbors) as usize,
+                        (state + 1) % gen
+                    );
+                }
+            }
+        }
+
+        let table = RuleTable::from_bsg(TableNeighborhood::Moore, &[], &[], 2).unwrap();
+        assert_eq!(table.n_states, 2);
+        assert_eq!(
+            RuleTable::from_bsg(TableNeighborhood::Moore, &[], &[], 1),
+            None
+        );
+        assert_eq!(
+            RuleTable::from_bsg(TableNeighborhood::Moore, &[], &[], 257),
+            None
+        );
+        assert_eq!(
+            RuleTable::from_bsg(TableNeighborhood::VonNeumann, &[0x10], &[], 3),
+            None
+        );
+
+        let table = RuleTable::from_bsg(TableNeighborhood::VonNeumann, &[], &[], 256).unwrap();
+        assert_eq!(table.n_states, 256);
+        assert_eq!(table.variables[0].states.len(), 256);
+        assert_eq!(table.variables[4].states.len(), 255);
+        assert_eq!(table.next_state(1, &[0, 0, 0, 0]), 2);
+        assert_eq!(table.next_state(254, &[1, 1, 0, 0]), 255);
+        assert_eq!(table.next_state(255, &[1, 1, 0, 0]), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn tree() -> Result<(), ParseRuleError> {
+        let life = <Rule as ParseNtLife>::parse_rule("B3/S23")?;
+        let tree = RuleTree::from_bs(TableNeighborhood::Moore, &life.b, &life.s).unwrap();
+        assert_eq!(tree.n_neighbors, 8);
+        assert_eq!(tree.nodes.len(), 32);
+        for i in 0..=0xffu8 {
+            let neighbors: Vec<u8> = [7, 5, 2, 0, 6, 4, 3, 1]
+                .iter()
+                .map(|bit| i >> bit & 1)
+                .collect();
+            assert_eq!(tree.next_state(0, &neighbors) == 1, life.b.contains(&i));
+            assert_eq!(tree.next_state(1, &neighbors) == 1, life.s.contains(&i));
+        }
+        assert_eq!(tree.next_state(0, &[2, 1, 1, 0, 0, 0, 0, 0]), 0);
+        assert_eq!(tree.next_state(2, &[1, 1, 1, 0, 0, 0, 0, 0]), 2);
+        // The output of Golly's RuleTreeGen for Life.
+        assert_eq!(
+            tree.to_string(),
+            "@TREE\n\
+             num_states=2\n\
+             num_neighbors=8\n\
+             num_nodes=32\n\
+             1 0 0\n\
+             2 0 0\n\
+             1 0 1\n\
+             2 0 2\n\
+             3 1 3\n\
+             1 1 1\n\
+             2 2 5\n\
+             3 3 6\n\
+             4 4 7\n\
+             2 5 0\n\
+             3 6 9\n\
+             4 7 10\n\
+             5 8 11\n\
+             3 9 1\n\
+             4 10 13\n\
+             5 11 14\n\
+             6 12 15\n\
+             3 1 1\n\
+             4 13 17\n\
+             5 14 18\n\
+             6 15 19\n\
+             7 16 20\n\
+             4 17 17\n\
+             5 18 22\n\
+             6 19 23\n\
+             7 20 24\n\
+             8 21 25\n\
+             5 22 22\n\
+             6 23 27\n\
+             7 24 28\n\
+             8 25 29\n\
+             9 26 30\n"
+        );
+
+        let hex = <Rule as ParseNtHex>::parse_rule("B2o3-o4m/S12m3o4m5H")?;
+        let tree = RuleTree::from_bs(TableNeighborhood::Hexagonal, &hex.b, &hex.s).unwrap();
+        assert_eq!(tree.n_neighbors, 8);
+        for i in 0..=0xffu8 {
+            let neighbors: Vec<u8> = [7, 5, 2, 0, 6, 4, 3, 1]
+                .iter()
+                .map(|bit| i >> bit & 1)
+                .collect();
+            // Drop the `NE` and `SW` neighbors.
+            let j = (i & 0xc0) >> 2 | (i & 0x18) >> 1 | (i & 0x03);
+            assert_eq!(tree.next_state(0, &neighbors) == 1, hex.b.contains(&j));
+            assert_eq!(tree.next_state(1, &neighbors) == 1, hex.s.contains(&j));
+        }
+
+        assert_eq!(
+            RuleTree::from_bs(TableNeighborhood::OneDimensional, &[], &[]),
+            None
+        );
+        assert_eq!(
+            RuleTree::from_bs(TableNeighborhood::VonNeumann, &[0x10], &[]),
+            None
+        );
+        assert_eq!(
+            RuleTree::from_bs(TableNeighborhood::Hexagonal, &[], &[0x40]),
+            None
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn without_table() {
+        let file = parse_rule_file("@RULE B3/S23\n\n@COLORS\n1 255 0 0\n").unwrap();
+        assert_eq!(file.name, "B3/S23");
+        assert_eq!(file.table, None);
+    }
+
+    #[test]
+    fn invalid_files() {
+        assert_eq!(
+            parse_rule_file("").err(),
+            Some(ParseRuleFileError::MissingRuleName)
+        );
+        assert_eq!(
+            parse_rule_file("@TABLE\nn_states:2").err(),
+            Some(ParseRuleFileError::MissingRuleName)
+        );
+        assert_eq!(
+            parse_rule_file("@RULE Test\n@TABLE\nn_states:1").err(),
+            Some(ParseRuleFileError::InvalidStates(3))
+        );
+        assert_eq!(
+            parse_rule_file("@RULE Test\n@TABLE\nn_states:2\nneighborhood:Foo").err(),
+            Some(ParseRuleFileError::UnknownNeighborhood(4))
+        );
+        assert_eq!(
+            parse_rule_file(
+                "@RULE Test\n@TABLE\nn_states:2\nneighborhood:vonNeumann\nsymmetries:rotate8"
+            )
+            .err(),
+            Some(ParseRuleFileError::UnknownSymmetries(5))
+        );
+        assert_eq!(
+            parse_rule_file("@RULE Test\n@TABLE\nn_states:2\nneighborhood:Moore").err(),
+            Some(ParseRuleFileError::MissingField("symmetries"))
+        );
+
+        let header = "@RULE Test\n@TABLE\nn_states:2\nneighborhood:vonNeumann\nsymmetries:none\n";
+        for line in [
+            "0,1,0,0,0",
+            "0,1,0,0,0,1,1",
+            "0,1,0,0,2,1",
+            "0,1,0,0,x,1",
+            "0,a,0,0,0,b",
+        ] {
+            let input = format!("{}var a={{0,1}}\nvar b={{0,1}}\n{}", header, line);
+            assert_eq!(
+                parse_rule_file(&input).err(),
+                Some(ParseRuleFileError::InvalidTransition(8)),
+                "{:?}",
+                line
+            );
+        }
+        assert_eq!(
+            parse_rule_file(&format!("{}var a={{0,2}}", header)).err(),
+            Some(ParseRuleFileError::InvalidVariable(6))
+        );
+        assert_eq!(
+            parse_rule_file(&format!("{}var a", header)).err(),
+            Some(ParseRuleFileError::InvalidVariable(6))
+        );
+    }
+}
@@ -19,7 +19,7 @@
 //! * [Non-isotropic von Neumann](http://www.conwaylife.com/wiki/Non-isotropic_Life-like_cellular_automaton),
 //!   e.g., `MAPHmlphg`.
 //! * The corresponding [Generations rules](http://www.conwaylife.com/wiki/Generations)
-//! of the above rules, e.g., `3457/357/5`.
+//!   of the above rules, e.g., `3457/357/5`.
 //!
 //! For non-Generations rules, four different notations are supported:
 //! * [B/S notation](http://www.conwaylife.com/wiki/Rulestring#B.2FS_notation) (`B3/S23`)
@@ -31,15 +31,86 @@
 //! For Generations rules, four different notations are supported:
 //!
 //! * B/S notation (`B357/S3457/C5`)
-//! * The notation used by [Golly](http://golly.sourceforge.net/Help/Algorithms/Generations.html) (`3457/357/5`)
+//! * The notation used by [Golly](http://golly.sourceforge.net/Help/Algorithms/Generations.html) (`3457/357/5`),
+//!   where the number of states may also be preceded by `C` or `G` (`3457/357/C5`)
 //! * The notation used by [Catagolue](https://catagolue.appspot.com/rules/generations) (`g5b357s3457`)
 //! * [MAP strings](http://www.conwaylife.com/wiki/Non-isotropic_Life-like_cellular_automaton)
 //!   for [non-isotropic rules](http://www.conwaylife.com/wiki/Non-isotropic_Life-like_cellular_automaton)
 //!   (`MAPARYBFxZpF38WaRd/aZZ//hZpF39pln/+aZZ//pZp/ukWaRd/aZZ//mmWf/6Waf7paZZ//pZp/umWaf7paZbplg/5`)
 //!
+//! In Generations rules, the neighborhood suffix of hexagonal and von Neumann rules
+//! (`H` or `V`) may come either last or right before the number of states,
+//! where the `/` before the number may be omitted,
+//! e.g., `B2/S34/C3H`, `B2/S34H/C3`, `34/2/3H`, `34/2H3` or `g3b2s34h`.
+//! The suffix may only appear once.
+//!
+//! The number of states of Generations rules is a `usize`, so the largest accepted number
+//! is [`usize::MAX`], which depends on the target: it is `18446744073709551615`
+//! on 64-bit targets, but only `4294967295` on 32-bit targets.
+//! Larger numbers are rejected with [`ParseRuleError::GenOverflow`].
+//!
+//! In B/S notation, the survival part may be omitted together with its `/`,
+//! so `B2` is the same as `B2/S`.
+//!
+//! The empty rule, with no births and no survivals, is valid in every notation,
+//! e.g., `B/S`, `/`, `bs` or `B/SH`.
+//!
+//! An extra `/` at the end of a complete rule string is rejected, e.g., `B3/S23/` or `23/3/`,
+//! though `23/` is fine, being the S/B rule with no births.
+//! In Generations rules, a `/` after the `b` / `s` data must be followed by the number of states.
+//!
+//! In totalistic rules, neighbor counts may also be given as ranges or separated by commas,
+//! e.g., `B3/S2-5` or `B3,6/S2,3`. Since `-` starts a negation in non-totalistic rules,
+//! [`ParseNtLife`] and [`ParseNtHex`] do not accept this, and reject e.g. `B3-4/S23`.
+//!
 //! Please refer to [Life Wiki](http://www.conwaylife.com/wiki/Rulestring) for detailed definitions and
 //! notations of these rule strings.
 //!
+//! Leading and trailing ASCII whitespace in a rule string is ignored,
+//! so `" B3/S23\n"` parses the same as `"B3/S23"`. Whitespace inside the rule string is not allowed,
+//! except in the data of MAP strings, which are often wrapped across lines.
+//!
+//! Rule strings may be followed by a [bounded grid](http://golly.sourceforge.net/Help/bounded.html)
+//! specification, e.g., `B3/S23:T100,100`. Use [`Topology::split`] to separate it from the rule.
+//!
+//! Non-Generations rules may also carry a `History`, `Super` or `Investigator` suffix,
+//! e.g., `B3/S23History`. Use [`Variant::split`] to separate it from the base rule.
+//!
+//! The `MAP` prefix is case-insensitive, and the data may use either the standard
+//! or the URL-safe Base64 alphabet, with or without padding.
+//!
+//! In Generations MAP strings, the number of states may be preceded by `G` or `C`,
+//! and the `/` before it may be omitted, e.g., `MAPHmlphg/G3` or `MAPHmlphgC3`.
+//! It may also come first, as in Catagolue, e.g., `g3MAPHmlphg`.
+//!
+//! Hexagonal MAP strings may also be written in the Moore neighborhood, as Golly does,
+//! as long as the NE and SW neighbors are ignored.
+//!
+//! Keywords such as `B`, `S` and `MAP` are case-insensitive by default.
+//! To require them to be uppercase, use the `parse_rule_with` method of the parser traits
+//! with [`ParseOptions`], which can also turn off S/B notation,
+//! or accept the extensions of LifeViewer, such as `B3_S23` or `B2/S34NH/C3`.
+//!
+//! The `parse_rule_at` and `parse_rule_at_with` methods of the parser traits return
+//! a [`ParseError`], which adds to the error the byte offset in the input where it was found.
+//!
+//! The neighborhood of a MAP string can be detected with [`map_neighborhood_size`]
+//! before choosing a parser.
+//!
+//! A list of rule strings, one per line, can be parsed with [`parse_rules`],
+//! or with [`parse_rules_reader`] when reading it from a file.
+//! The rule of a pattern file can be found in its RLE header with [`parse_rle_header`].
+//!
+//! Rule tables in [Golly `.rule` files](http://golly.sourceforge.net/Help/formats.html#rule)
+//! can be read with the [`golly`] module.
+//!
+//! The `b` / `s` data passed to `from_bs` and `from_bsg` are always sorted and
+//! free of duplicates, so rule strings that differ only in the order or repetition
+//! of their numbers and letters, e.g., `B2kc/S` and `B2ck/S`, give equal data.
+//!
+//! In non-totalistic rules, a letter must be valid for the count before it,
+//! so `B2q/S23` is rejected with [`ParseRuleError::Unexpected`].
+//!
 //! # Example:
 //! ```
 //! use ca_rules::ParseLife;
@@ -72,11 +143,25 @@
 //! ```
 
 mod error;
+pub mod golly;
+mod lifeviewer;
+mod list;
 mod macros;
+mod map;
+mod options;
+mod rle;
 mod rules;
+mod topology;
+mod variant;
 
-pub use error::ParseRuleError;
+pub use error::{MapDecodeError, ParseError, ParseRuleError, ParseRuleFileError};
+pub use list::{parse_rules, parse_rules_reader};
+pub use map::map_neighborhood_size;
+pub use options::ParseOptions;
+pub use rle::{extract_rule, parse_rle_header};
 pub use rules::*;
+pub use topology::{Topology, TopologyKind};
+pub use variant::Variant;
 
 #[cfg(test)]
 mod test {
@@ -108,9 +193,7 @@ mod test {
     #[test]
     fn base64() -> Result<(), ParseRuleError> {
         let s = "MAPARYXfhZofugWaH7oaIDogBZofuhogOiAaIDogIAAgAAWaH7oaIDogGiA6ICAAIAAaIDogIAAgACAAIAAAAAAAA";
-        let bytes = ENGINE
-            .decode(&s[3..])
-            .map_err(|_| ParseRuleError::Base64Error)?;
+        let bytes = ENGINE.decode(&s[3..]).unwrap();
         assert_eq!(bytes.len(), 0x200 / 8);
         let mut b = Vec::new();
         let mut s = Vec::new();
@@ -0,0 +1,86 @@
+//! Compatibility with the rule strings accepted by LifeViewer.
+
+use crate::{ParseError, ParseOptions, ParseRuleError};
+
+/// Rewrites the extensions accepted by [`ParseOptions::lifeviewer`]
+/// into the notation of the strict parsers.
+///
+/// Also returns the byte offset in the input of each byte of the rewritten string,
+/// followed by the length of the input.
+fn normalize(input: &str) -> (String, Vec<usize>) {
+    let start = input.len()
+        - input
+            .trim_start_matches(|c: char| c.is_ascii_whitespace())
+            .len();
+    let mut output = String::with_capacity(input.len());
+    let mut origin = Vec::with_capacity(input.len() + 1);
+    let mut push = |c: char, i: usize| {
+        output.push(c);
+        origin.extend(std::iter::repeat_n(i, c.len_utf8()));
+    };
+    let mut chars = input.char_indices().peekable();
+    let mut prev = None;
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '_' => push('/', i),
+            // A neighborhood: `NM` for Moore, `NH` for hexagonal or `NN` for von Neumann.
+            'N' => match chars.peek() {
+                Some(&(_, 'M')) => {
+                    chars.next();
+                }
+                Some(&(_, 'H')) => {
+                    chars.next();
+                    push('H', i);
+                }
+                Some(&(_, 'N')) => {
+                    chars.next();
+                    push('V', i);
+                }
+                _ => push(c, i),
+            },
+            // The number of states without a `/` before it.
+            'C' | 'G' if i > start && !matches!(prev, Some('/' | '_')) => {
+                push('/', i);
+                push(c, i);
+            }
+            _ => push(c, i),
+        }
+        prev = Some(c);
+    }
+    origin.push(input.len());
+    (output, origin)
+}
+
+/// Parses `input` with `parser` after rewriting the extensions accepted by
+/// [`ParseOptions::lifeviewer`], so that the offsets of errors refer to `input`.
+pub(crate) fn parse_with<T>(
+    input: &str,
+    opts: &ParseOptions,
+    parser: fn(&str, &ParseOptions) -> Result<T, ParseError>,
+) -> Result<T, ParseError> {
+    let (normalized, origin) = normalize(input);
+    let opts = ParseOptions {
+        lifeviewer: false,
+        ..*opts
+    };
+    parser(&normalized, &opts).map_err(|mut e| {
+        if let ParseRuleError::ExtraJunk { found, offset } = &mut e.kind {
+            *offset = origin[*offset];
+            *found = input[*offset..].chars().next().unwrap();
+        }
+        e.offset = origin[e.offset];
+        e
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_offsets() {
+        let (normalized, origin) = normalize(" 34_2C3NH");
+        assert_eq!(normalized, " 34/2/C3H");
+        assert_eq!(origin, [0, 1, 2, 3, 4, 5, 5, 6, 7, 9]);
+    }
+}
@@ -0,0 +1,202 @@
+//! Parsing lists of rule strings.
+
+use crate::ParseRuleError;
+use std::io::{self, BufRead};
+
+/// Parses a list of rule strings, one per line, with the given parser.
+///
+/// Blank lines and lines starting with `#` are skipped.
+/// Each line is passed to the parser as it is, so the offsets in errors are those in the line.
+/// Each parsed line yields its 1-based line number along with the result,
+/// so that failures can be reported without stopping at the first one.
+///
+/// # Examples
+///
+/// ```
+/// use ca_rules::{parse_rules, ParseLife, ParseRuleError};
+///
+/// #[derive(Debug, Eq, PartialEq)]
+/// struct Rule {
+///     b: Vec<u8>,
+///     s: Vec<u8>,
+/// }
+///
+/// impl ParseLife for Rule {
+///     fn from_bs(b: Vec<u8>, s: Vec<u8>) -> Self {
+///         Rule { b, s }
+///     }
+/// }
+///
+/// let list = "# Some rules\nB3/S23\n\nB36/S23\nB3/S2x\n";
+/// let rules: Vec<_> = parse_rules(list, Rule::parse_rule).collect();
+///
+/// assert_eq!(rules.len(), 3);
+/// assert_eq!(rules[1], (4, Rule::parse_rule("B36/S23")));
+/// assert_eq!(rules[2], (5, Err(ParseRuleError::extra_junk('x', 5))));
+/// ```
+pub fn parse_rules<'a, T, F>(
+    input: &'a str,
+    parser: F,
+) -> impl Iterator<Item = (usize, Result<T, ParseRuleError>)> + 'a
+where
+    F: Fn(&str) -> Result<T, ParseRuleError> + 'a,
+{
+    input
+        .lines()
+        .enumerate()
+        .filter_map(move |(i, line)| parse_line(i, line, &parser))
+}
+
+/// Parses a list of rule strings, one per line, read from `reader` with the given parser.
+///
+/// This is the same as [`parse_rules`], except that the lines are read one at a time,
+/// so that large lists need not be loaded into memory at once.
+/// An I/O error, including a line that is not valid UTF-8, is yielded as an `Err`.
+///
+/// # Examples
+///
+/// ```
+/// use ca_rules::{parse_rules_reader, ParseLife};
+///
+/// struct Rule;
+///
+/// impl ParseLife for Rule {
+///     fn from_bs(_b: Vec<u8>, _s: Vec<u8>) -> Self {
+///         Rule
+///     }
+/// }
+///
+/// let reader = "# Some rules\nB3/S23\n\nB36/S23\nB3/S2x\n".as_bytes();
+/// let lines: Vec<_> = parse_rules_reader(reader, Rule::parse_rule)
+///     .map(|line| line.map(|(i, rule)| (i, rule.is_ok())))
+///     .collect::<Result<_, _>>()
+///     .unwrap();
+///
+/// assert_eq!(lines, vec![(2, true), (4, true), (5, false)]);
+/// ```
+pub fn parse_rules_reader<R, T, F>(
+    reader: R,
+    parser: F,
+) -> impl Iterator<Item = io::Result<(usize, Result<T, ParseRuleError>)>>
+where
+    R: BufRead,
+    F: Fn(&str) -> Result<T, ParseRuleError>,
+{
+    reader
+        .lines()
+        .enumerate()
+        .filter_map(move |(i, line)| match line {
+            Ok(line) => parse_line(i, &line, &parser).map(Ok),
+            Err(e) => Some(Err(e)),
+        })
+}
+
+/// Parses the line with index `i`, unless it is blank or a comment.
+fn parse_line<T, F>(i: usize, line: &str, parser: &F) -> Option<(usize, Result<T, ParseRuleError>)>
+where
+    F: Fn(&str) -> Result<T, ParseRuleError>,
+{
+    let trimmed = line.trim_matches(|c: char| c.is_ascii_whitespace());
+    if trimmed.is_empty() || trimmed.starts_with('#') {
+        None
+    } else {
+        Some((i + 1, parser(line)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ParseLifeGen, ParseNtLife};
+
+    #[derive(Debug, Eq, PartialEq)]
+    struct Rule {
+        b: Vec<u8>,
+        s: Vec<u8>,
+    }
+
+    impl ParseNtLife for Rule {
+        fn from_bs(b: Vec<u8>, s: Vec<u8>) -> Self {
+            Rule { b, s }
+        }
+    }
+
+    #[derive(Debug, Eq, PartialEq)]
+    struct GenRule {
+        b: Vec<u8>,
+        s: Vec<u8>,
+        gen: usize,
+    }
+
+    impl ParseLifeGen for GenRule {
+        fn from_bsg(b: Vec<u8>, s: Vec<u8>, gen: usize) -> Self {
+            GenRule { b, s, gen }
+        }
+    }
+
+    #[test]
+    fn rule_list() -> Result<(), ParseRuleError> {
+        let list = "B3/S23\r\n  # comment\r\n\r\n  B2-a/S12  \r\nB3/S2x\r\n#B3/S2x";
+        let rules: Vec<_> = parse_rules(list, Rule::parse_rule).collect();
+        assert_eq!(
+            rules,
+            vec![
+                (1, Ok(Rule::parse_rule("B3/S23")?)),
+                (4, Ok(Rule::parse_rule("B2-a/S12")?)),
+                (5, Err(ParseRuleError::Unexpected('x'))),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn rule_list_reader() -> Result<(), ParseRuleError> {
+        let list = "B3/S23\r\n  # comment\r\n\r\n  B2-a/S12  \r\nB3/S2x\r\n#B3/S2x\n";
+        let rules: Vec<_> = parse_rules_reader(list.as_bytes(), Rule::parse_rule)
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(
+            rules,
+            parse_rules(list, Rule::parse_rule).collect::<Vec<_>>()
+        );
+        let mut lines = parse_rules_reader(&b"B3/S23\n\xff\nB36/S23"[..], Rule::parse_rule);
+        assert_eq!(
+            lines.next().unwrap().unwrap(),
+            (1, Rule::parse_rule("B3/S23"))
+        );
+        assert_eq!(
+            lines.next().unwrap().unwrap_err().kind(),
+            io::ErrorKind::InvalidData
+        );
+        assert_eq!(
+            lines.next().unwrap().unwrap(),
+            (3, Rule::parse_rule("B36/S23"))
+        );
+        assert!(lines.next().is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn offsets_in_line() {
+        let lines: Vec<_> = parse_rules("  B3/S2x\n\tB3/S23\t", GenRule::parse_rule)
+            .map(|(i, rule)| (i, rule.map(|rule| rule.gen)))
+            .collect();
+        assert_eq!(
+            lines,
+            vec![(1, Err(ParseRuleError::extra_junk('x', 7))), (2, Ok(2))]
+        );
+    }
+
+    #[test]
+    fn gen_rule_list() {
+        let list = "3457/357/5\ng4b2s\nB3/S23";
+        let lines: Vec<_> = parse_rules(list, GenRule::parse_rule)
+            .map(|(i, rule)| (i, rule.map(|rule| rule.gen)))
+            .collect();
+        assert_eq!(lines, vec![(1, Ok(5)), (2, Ok(4)), (3, Ok(2))]);
+        assert_eq!(
+            parse_rules("\n# only comments\n", GenRule::parse_rule).count(),
+            0
+        );
+    }
+}
@@ -23,12 +23,41 @@ macro_rules! rule_struct {
                 }
             }
 
+            /// An error for the character `found`, followed by `rest`,
+            /// after the end of a rule string that ends at byte `end` of the input.
+            fn extra_junk<I>(found: char, rest: I, end: usize) -> ParseRuleError
+            where
+                I: Iterator<Item = char>,
+            {
+                let offset = end - found.len_utf8() - rest.map(char::len_utf8).sum::<usize>();
+                ParseRuleError::ExtraJunk { found, offset }
+            }
+
+            /// Adds to an error the byte offset where parsing stopped, given the unparsed
+            /// rest `chars` of a rule string that ends at byte `end` of the input.
+            fn error_at<I>(kind: ParseRuleError, chars: I, end: usize) -> crate::ParseError
+            where
+                I: Iterator<Item = char>,
+            {
+                crate::ParseError::at(kind, end - chars.map(char::len_utf8).sum::<usize>())
+            }
+
+            /// Checks that a keyword is uppercase, if the options require it.
+            fn check_case(c: char, opts: &crate::ParseOptions) -> Result<(), ParseRuleError> {
+                if opts.case_sensitive && c.is_ascii_lowercase() {
+                    Err(ParseRuleError::Unexpected(c))
+                } else {
+                    Ok(())
+                }
+            }
+
             /// A parser for numbers.
             fn parse_num<I>(chars: &mut std::iter::Peekable<I>) -> Result<usize, ParseRuleError>
             where
-                I: Iterator<Item = char>,
+                I: Iterator<Item = char> + Clone,
             {
                 let mut n: usize = 0;
+                let start = chars.clone();
                 if chars.peek().is_none() || !chars.peek().unwrap().is_digit(10) {
                     return Err(ParseRuleError::MissingNumber);
                 }
@@ -36,11 +65,17 @@ macro_rules! rule_struct {
                     match c {
                         c if c.is_digit(10) => {
                             chars.next();
-                            n = n
+                            match n
                                 .checked_mul(10)
-                                .ok_or(ParseRuleError::GenOverflow)?
-                                .checked_add(c.to_digit(10).unwrap() as usize)
-                                .ok_or(ParseRuleError::GenOverflow)?;
+                                .and_then(|n| n.checked_add(c.to_digit(10).unwrap() as usize))
+                            {
+                                Some(m) => n = m,
+                                None => {
+                                    // The error points at the start of the number.
+                                    *chars = start;
+                                    return Err(ParseRuleError::GenOverflow);
+                                }
+                            }
                         }
                         _ => return Ok(n),
                     }
@@ -54,236 +89,341 @@ macro_rules! rule_struct {
 /// A macro to define a function to parse the helper struct.
 macro_rules! parse_rule {
     ($($suffix: expr)?) => {
+        /// A parser for the struct, with the default options.
+        /// Parses the keyword `keyword`, in either case unless the options require it to be uppercase.
+        fn parse_keyword<I>(
+            chars: &mut std::iter::Peekable<I>,
+            keyword: char,
+            opts: &crate::ParseOptions,
+        ) -> Result<(), ParseRuleError>
+        where
+            I: Iterator<Item = char>,
+        {
+            match chars.peek() {
+                Some(&c) if c.eq_ignore_ascii_case(&keyword) => {
+                    Self::check_case(c, opts)?;
+                    chars.next();
+                    Ok(())
+                }
+                _ => Err(ParseRuleError::Missing(keyword)),
+            }
+        }
+
         /// A parser for the struct.
-        fn parse_rule(input: &str) -> Result<Self, ParseRuleError> {
+        fn parse_rule_with(
+            input: &str,
+            opts: &crate::ParseOptions,
+        ) -> Result<Self, crate::ParseError> {
+            if opts.lifeviewer {
+                return crate::lifeviewer::parse_with(input, opts, Self::parse_rule_with);
+            }
+            let end = input.trim_end_matches(|c: char| c.is_ascii_whitespace()).len();
+            let input = input.trim_matches(|c: char| c.is_ascii_whitespace());
             let mut chars = input.chars().peekable();
-            let (b, s);
+            let mut parse = || -> Result<Self, ParseRuleError> {
+                if input.is_empty() {
+                    return Err(ParseRuleError::EmptyInput);
+                }
+                let (b, s);
 
-            match chars.peek() {
-                Some('B') | Some('b') => {
-                    // Rule strings using B/S notation
-                    chars.next();
-                    b = Self::parse_bs(&mut chars)?;
-                    if chars.peek() == Some(&'/') {
+                match chars.peek() {
+                    Some(&c @ ('B' | 'b')) => {
+                        // Rule strings using B/S notation
+                        Self::check_case(c, opts)?;
                         chars.next();
+                        b = Self::parse_bs(&mut chars)?;
+                        // The survival part may be omitted entirely, e.g., `B2` for `B2/S`.
+                        let mut rest = chars.clone();
+                        let bare = match rest.next() {
+                            None => true,
+                            $(Some(c) if c.eq_ignore_ascii_case(&$suffix) => rest.next().is_none(),)?
+                            Some(_) => false,
+                        };
+                        if bare {
+                            s = Vec::new();
+                        } else {
+                            chars.next_if_eq(&'/');
+                            Self::parse_keyword(&mut chars, 'S', opts)?;
+                            s = Self::parse_bs(&mut chars)?;
+                        }
                     }
-                    match chars.next() {
-                        Some('S') | Some('s') => (),
-                        _ => return Err(ParseRuleError::Missing('S')),
-                    }
-                    s = Self::parse_bs(&mut chars)?;
-                }
-                _ => {
-                    // Rule strings using S/B notation
-                    s = Self::parse_bs(&mut chars)?;
-                    match chars.next() {
-                        Some('/') => (),
-                        _ => return Err(ParseRuleError::Missing('/')),
+                    _ => {
+                        // Rule strings using S/B notation
+                        if !opts.allow_sb_notation {
+                            return Err(ParseRuleError::Missing('B'));
+                        }
+                        s = Self::parse_bs(&mut chars)?;
+                        if chars.next_if_eq(&'/').is_none() {
+                            return Err(ParseRuleError::Missing('/'));
+                        }
+                        b = Self::parse_bs(&mut chars)?;
                     }
-                    b = Self::parse_bs(&mut chars)?;
                 }
-            }
 
-            $(
                 // Suffix
-                if let Some(c) = chars.next() {
-                    if $suffix.to_lowercase().chain($suffix.to_uppercase()).all(|s| s != c) {
-                        return Err(ParseRuleError::Missing($suffix));
-                    }
-                } else {
-                    return Err(ParseRuleError::Missing($suffix));
-                }
-            )?
+                $(Self::parse_keyword(&mut chars, $suffix, opts)?;)?
 
-            match chars.next() {
-                None => Ok(Self::from_bs(b, s)),
-                _ => Err(ParseRuleError::ExtraJunk),
-            }
+                match chars.next() {
+                    None => Ok(Self::from_bs(b, s)),
+                    Some(c) => Err(Self::extra_junk(c, &mut chars, end)),
+                }
+            };
+            let result = parse();
+            result.map_err(|kind| Self::error_at(kind, chars, end))
         }
 
+        /// A parser for the Generations struct, with the default options.
+        ///
+        /// If `lenient` is true, a number of states less than 2 is treated as 2.
         /// A parser for the Generations struct.
-        fn parse_rule_gen(input: &str) -> Result<Gen<Self>, ParseRuleError> {
+        fn parse_rule_gen_with(
+            input: &str,
+            opts: &crate::ParseOptions,
+        ) -> Result<Gen<Self>, crate::ParseError> {
+            if opts.lifeviewer {
+                return crate::lifeviewer::parse_with(input, opts, Self::parse_rule_gen_with);
+            }
+            let end = input.trim_end_matches(|c: char| c.is_ascii_whitespace()).len();
+            let input = input.trim_matches(|c: char| c.is_ascii_whitespace());
             let mut chars = input.chars().peekable();
-            let (b, s);
-            let mut gen = 2;
+            let mut parse = || -> Result<Gen<Self>, ParseRuleError> {
+                if input.is_empty() {
+                    return Err(ParseRuleError::EmptyInput);
+                }
+                let (base, variant) = crate::Variant::split(input);
+                if variant != crate::Variant::None {
+                    // The error points at the variant suffix.
+                    chars = input[base.len()..].chars().peekable();
+                    return Err(ParseRuleError::VariantInGen);
+                }
+                let (b, s);
+                let mut gen = 2;
+                // The rest of the rule string from the number of states, for errors about it.
+                let mut gen_chars = chars.clone();
+                // The suffix may come either right after the `b` / `s` data, or at the end.
+                let suffix: Option<char> = None $(.or(Some($suffix)))?;
+                let parse_suffix = |chars: &mut std::iter::Peekable<std::str::Chars>| {
+                    match suffix.and_then(|suffix| {
+                        chars.peek().copied().filter(|c| c.eq_ignore_ascii_case(&suffix))
+                    }) {
+                        Some(c) => {
+                            Self::check_case(c, opts)?;
+                            chars.next();
+                            Ok(true)
+                        }
+                        None => Ok(false),
+                    }
+                };
+                let suffixed;
 
-            match chars.peek() {
-                // Rule strings using B/S/G notation
-                Some('B') | Some('b') => {
-                    chars.next();
-                    b = Self::parse_bs(&mut chars)?;
-                    if chars.peek() == Some(&'/') {
+                match chars.peek() {
+                    // Rule strings using B/S/G notation
+                    Some(&c @ ('B' | 'b')) => {
+                        Self::check_case(c, opts)?;
                         chars.next();
-                    }
-                    match chars.next() {
-                        Some('S') | Some('s') => (),
-                        _ => return Err(ParseRuleError::Missing('S')),
-                    }
-                    s = Self::parse_bs(&mut chars)?;
-                    match chars.peek() {
-                        Some('/') => {
-                            chars.next();
-                            match chars.peek() {
-                                Some('C') | Some('c') | Some('G') | Some('g') => {
+                        b = Self::parse_bs(&mut chars)?;
+                        chars.next_if_eq(&'/');
+                        Self::parse_keyword(&mut chars, 'S', opts)?;
+                        s = Self::parse_bs(&mut chars)?;
+                        suffixed = parse_suffix(&mut chars)?;
+                        match chars.peek() {
+                            Some('/') => {
+                                chars.next();
+                                if let Some(&c) = chars.peek().filter(|c| "CcGg".contains(**c)) {
+                                    Self::check_case(c, opts)?;
                                     chars.next();
                                 }
-                                _ => (),
+                                gen_chars = chars.clone();
+                                gen = Self::parse_num(&mut chars)?;
                             }
-                            gen = Self::parse_num(&mut chars)?;
-                        }
-                        Some('C') | Some('c') | Some('G') | Some('g') => {
-                            chars.next();
-                            gen = Self::parse_num(&mut chars)?;
+                            Some(&c @ ('C' | 'c' | 'G' | 'g')) => {
+                                Self::check_case(c, opts)?;
+                                chars.next();
+                                gen_chars = chars.clone();
+                                gen = Self::parse_num(&mut chars)?;
+                            }
+                            Some(c) if suffixed && c.is_ascii_digit() => {
+                                gen_chars = chars.clone();
+                                gen = Self::parse_num(&mut chars)?;
+                            }
+                            _ => (),
                         }
-                        _ => (),
                     }
-                }
 
-                // Rule strings using G/B/S notation
-                Some('C') | Some('c') | Some('G') | Some('g') => {
-                    chars.next();
-                    gen = Self::parse_num(&mut chars)?;
-                    if chars.peek() == Some(&'/') {
-                        chars.next();
-                    }
-                    match chars.next() {
-                        Some('B') | Some('b') => (),
-                        _ => return Err(ParseRuleError::Missing('B')),
-                    }
-                    b = Self::parse_bs(&mut chars)?;
-                    if chars.peek() == Some(&'/') {
+                    // Rule strings using G/B/S notation
+                    Some(&c @ ('C' | 'c' | 'G' | 'g')) => {
+                        Self::check_case(c, opts)?;
                         chars.next();
+                        gen_chars = chars.clone();
+                        gen = Self::parse_num(&mut chars)?;
+                        chars.next_if_eq(&'/');
+                        Self::parse_keyword(&mut chars, 'B', opts)?;
+                        b = Self::parse_bs(&mut chars)?;
+                        chars.next_if_eq(&'/');
+                        Self::parse_keyword(&mut chars, 'S', opts)?;
+                        s = Self::parse_bs(&mut chars)?;
+                        suffixed = false;
                     }
-                    match chars.next() {
-                        Some('S') | Some('s') => (),
-                        _ => return Err(ParseRuleError::Missing('S')),
+
+                    // Rule strings using S/B/G notation
+                    _ => {
+                        if !opts.allow_sb_notation {
+                            return Err(ParseRuleError::Missing('B'));
+                        }
+                        s = Self::parse_bs(&mut chars)?;
+                        if chars.next_if_eq(&'/').is_none() {
+                            return Err(ParseRuleError::Missing('/'));
+                        }
+                        b = Self::parse_bs(&mut chars)?;
+                        suffixed = parse_suffix(&mut chars)?;
+                        if chars.next_if_eq(&'/').is_some() {
+                            if let Some(&c) = chars.peek().filter(|c| "CcGg".contains(**c)) {
+                                Self::check_case(c, opts)?;
+                                chars.next();
+                            }
+                            gen_chars = chars.clone();
+                            gen = Self::parse_num(&mut chars)?;
+                        } else if suffixed && chars.peek().is_some_and(char::is_ascii_digit) {
+                            gen_chars = chars.clone();
+                            gen = Self::parse_num(&mut chars)?;
+                        }
                     }
-                    s = Self::parse_bs(&mut chars)?;
                 }
 
-                // Rule strings using S/B/G notation
-                _ => {
-                    s = Self::parse_bs(&mut chars)?;
-                    match chars.next() {
-                        Some('/') => (),
-                        _ => return Err(ParseRuleError::Missing('/')),
-                    }
-                    b = Self::parse_bs(&mut chars)?;
-                    if chars.peek() == Some(&'/') {
-                        chars.next();
-                        gen = Self::parse_num(&mut chars)?;
+                if let Some(suffix) = suffix {
+                    if !suffixed && !parse_suffix(&mut chars)? {
+                        return Err(ParseRuleError::Missing(suffix));
                     }
                 }
-            }
 
-            $(
-                // Suffix
-                if let Some(c) = chars.next() {
-                    if $suffix.to_lowercase().chain($suffix.to_uppercase()).all(|s| s != c) {
-                        return Err(ParseRuleError::Missing($suffix));
+                if gen < 2 {
+                    if !opts.lenient {
+                        chars = gen_chars;
+                        return Err(ParseRuleError::GenLessThan2);
                     }
-                } else {
-                    return Err(ParseRuleError::Missing($suffix));
+                    gen = 2;
                 }
-            )?
-
-            if gen < 2 {
-                Err(ParseRuleError::GenLessThan2)
-            } else {
                 match chars.next() {
                     None => Ok(Self::from_bsg(b, s, gen)),
-                    _ => Err(ParseRuleError::ExtraJunk),
+                    Some(c) => Err(Self::extra_junk(c, &mut chars, end)),
                 }
-            }
+            };
+            let result = parse();
+            result.map_err(|kind| Self::error_at(kind, chars, end))
         }
     };
 }
 
 /// A macro to define a function to parse `b` / `s` data.
 macro_rules! parse_bs {
-    ($n: expr) => {
+    // Non-totalistic rules, with a table of the neighborhoods of each letter for each count.
+    (hensel $table: expr) => {
         /// A parser for `b` / `s` data.
         fn parse_bs<I>(chars: &mut std::iter::Peekable<I>) -> Result<Vec<u8>, ParseRuleError>
         where
             I: Iterator<Item = char>,
         {
+            let table: &[&[(char, &[u8])]] = &$table;
+            // Whether `c` is a letter of any count.
+            let is_letter = |c: char| table.iter().flat_map(|l| l.iter()).any(|&(l, _)| l == c);
             let mut bs = Vec::new();
 
             while let Some(&c) = chars.peek() {
-                match c {
-                    c if c.is_digit($n + 1) => {
-                        chars.next();
-                        bs.push(c.to_digit($n + 1).unwrap() as u8);
+                let count = match c.to_digit(10) {
+                    Some(n) if (n as usize) < table.len() => n as usize,
+                    Some(n) => return Err(ParseRuleError::CountTooLarge(n as u8)),
+                    None => break,
+                };
+                chars.next();
+                let letters = table[count];
+                let negated = chars.next_if_eq(&'-').is_some();
+                let mut keys = Vec::new();
+                while let Some(c) = chars.next_if(|&c| letters.iter().any(|&(l, _)| l == c)) {
+                    keys.push(c);
+                }
+                // Letters that are not valid for this count. Other letters,
+                // e.g., `S` or `H`, may start the next part of the rule string.
+                match chars.peek() {
+                    Some(&c)
+                        if is_letter(c)
+                            || c.is_ascii_alphabetic() && !"bcghsvBCGHSV".contains(c) =>
+                    {
+                        return Err(ParseRuleError::Unexpected(c));
+                    }
+                    _ => (),
+                }
+                if negated && keys.is_empty() {
+                    return Err(ParseRuleError::EmptyNegation(count as u8));
+                }
+                for &(letter, masks) in letters {
+                    if keys.is_empty() || keys.contains(&letter) != negated {
+                        bs.extend_from_slice(masks);
                     }
-                    _ => break,
                 }
             }
 
             bs.sort_unstable();
+            bs.dedup();
             Ok(bs)
         }
     };
 
-    { $($count: expr => { $($key: expr => $value: expr),* $(,)? }),*  $(,)? } => {
+    ($n: expr) => {
         /// A parser for `b` / `s` data.
         fn parse_bs<I>(chars: &mut std::iter::Peekable<I>) -> Result<Vec<u8>, ParseRuleError>
         where
-            I: Iterator<Item = char>,
+            I: Iterator<Item = char> + Clone,
         {
+            /// A parser for a single neighbor count.
+            fn parse_count<I>(chars: &mut std::iter::Peekable<I>) -> Result<u8, ParseRuleError>
+            where
+                I: Iterator<Item = char>,
+            {
+                match chars.peek() {
+                    Some(&c) if c.is_digit($n + 1) => {
+                        chars.next();
+                        Ok(c.to_digit($n + 1).unwrap() as u8)
+                    }
+                    Some(&c) if c.is_ascii_digit() => {
+                        Err(ParseRuleError::CountTooLarge(c.to_digit(10).unwrap() as u8))
+                    }
+                    _ => Err(ParseRuleError::MissingNumber),
+                }
+            }
+
             let mut bs = Vec::new();
 
             while let Some(&c) = chars.peek() {
                 match c {
-                    $(
-                        $count => {
-                            chars.next();
-                            let all_keys = vec![$($key),*];
-                            let keys = match chars.peek() {
-                                Some('-') => {
-                                    chars.next();
-                                    let mut keys = Vec::new();
-                                    while let Some(&c) = chars.peek() {
-                                        if all_keys.contains(&c) {
-                                            chars.next();
-                                            keys.push(c);
-                                        } else {
-                                            break;
-                                        }
-                                    }
-                                    all_keys.into_iter().filter(|c| !keys.contains(c)).collect()
-                                }
-                                Some(c) if all_keys.contains(&c) => {
-                                    let mut keys = Vec::new();
-                                    while let Some(&c) = chars.peek() {
-                                        if all_keys.contains(&c) {
-                                            chars.next();
-                                            keys.push(c);
-                                        } else {
-                                            break;
-                                        }
-                                    }
-                                    keys
-                                }
-                                Some(_) => {
-                                    all_keys
-                                }
-                                None => all_keys
-                            };
-                            for &c in keys.iter() {
-                                match c {
-                                    $(
-                                        $key => bs.extend_from_slice(&($value)),
-                                    )*
-                                    _ => unreachable!(),
-                                }
+                    c if c.is_ascii_digit() => {
+                        let start = chars.clone();
+                        let min = parse_count(chars)?;
+                        // A range of counts, e.g., `2-5`.
+                        if chars.next_if_eq(&'-').is_some() {
+                            let max = parse_count(chars)?;
+                            if min > max {
+                                // The error points at the start of the range.
+                                *chars = start;
+                                return Err(ParseRuleError::InvalidRange(min, max));
                             }
+                            bs.extend(min..=max);
+                        } else {
+                            bs.push(min);
+                        }
+                    }
+                    // A separator between counts, e.g., `2,5`.
+                    ',' if !bs.is_empty() => {
+                        chars.next();
+                        if !chars.peek().is_some_and(char::is_ascii_digit) {
+                            return Err(ParseRuleError::MissingNumber);
                         }
-                    ),*
+                    }
                     _ => break,
                 }
             }
 
             bs.sort_unstable();
+            bs.dedup();
             Ok(bs)
         }
     };
@@ -292,31 +432,44 @@ macro_rules! parse_bs {
 /// A macro to define a function to parse MAP strings.
 macro_rules! parse_rule_map {
     ($n: expr) => {
+        /// A parser for the struct that parses MAP strings, with the default options.
         /// A parser for the struct that parses MAP strings.
-        fn parse_rule_map(input: &str) -> Result<Self, ParseRuleError> {
-            use base64::{
-                alphabet::STANDARD,
-                engine::{
-                    general_purpose::{GeneralPurpose, GeneralPurposeConfig},
-                    DecodePaddingMode, Engine,
-                },
-            };
-
+        fn parse_rule_map_with(
+            input: &str,
+            opts: &crate::ParseOptions,
+        ) -> Result<Self, crate::ParseError> {
             const CENTER_MARK: usize = 1 << ($n / 2);
             const RIGHT_MARK: usize = CENTER_MARK - 1;
             const LEFT_MARK: usize = RIGHT_MARK << ($n / 2 + 1);
-            const ENGINE_CONFIG: GeneralPurposeConfig = GeneralPurposeConfig::new()
-                .with_decode_padding_mode(DecodePaddingMode::Indifferent);
-            const ENGINE: GeneralPurpose = GeneralPurpose::new(&STANDARD, ENGINE_CONFIG);
 
-            if !input.starts_with("MAP") {
-                return Err(ParseRuleError::NotMapRule);
+            let end = input
+                .trim_end_matches(|c: char| c.is_ascii_whitespace())
+                .len();
+            let input = input.trim_matches(|c: char| c.is_ascii_whitespace());
+            let start = end - input.len();
+            if input.is_empty() {
+                return Err(crate::ParseError::at(ParseRuleError::EmptyInput, end));
             }
-            let bytes = ENGINE
-                .decode(&input[3..])
-                .map_err(|_| ParseRuleError::Base64Error)?;
+            if !input
+                .get(..3)
+                .is_some_and(|prefix| prefix.eq_ignore_ascii_case("MAP"))
+            {
+                return Err(crate::ParseError::at(ParseRuleError::NotMapRule, start));
+            }
+            for (i, c) in input[..3].char_indices() {
+                Self::check_case(c, opts).map_err(|e| crate::ParseError::at(e, start + i))?;
+            }
+            let data = &input[3..];
+            let bytes = crate::map::decode(data).map_err(|e| {
+                let offset = start + 3 + crate::map::error_offset(data, &e);
+                crate::ParseError::at(e, offset)
+            })?;
             if bytes.len() * 8 != 2 << $n {
-                return Err(ParseRuleError::InvalidLength);
+                let e = ParseRuleError::InvalidLength {
+                    expected: 2 << $n,
+                    found: bytes.len() * 8,
+                };
+                return Err(crate::ParseError::at(e, start + 3));
             }
             let mut b = Vec::new();
             let mut s = Vec::new();
@@ -336,45 +489,120 @@ macro_rules! parse_rule_map {
             Ok(Self::from_bs(b, s))
         }
 
+        /// A parser for the Generations struct that parses MAP strings, with the default options.
+        ///
+        /// If `lenient` is true, a number of states less than 2 is treated as 2.
         /// A parser for the Generations struct that parses MAP strings.
-        fn parse_rule_gen_map(input: &str) -> Result<Gen<Self>, ParseRuleError> {
-            use base64::{
-                alphabet::STANDARD,
-                engine::{
-                    general_purpose::{GeneralPurpose, GeneralPurposeConfig},
-                    DecodePaddingMode, Engine,
-                },
-            };
-
+        fn parse_rule_gen_map_with(
+            input: &str,
+            opts: &crate::ParseOptions,
+        ) -> Result<Gen<Self>, crate::ParseError> {
             const CENTER_MARK: usize = 1 << ($n / 2);
             const RIGHT_MARK: usize = CENTER_MARK - 1;
             const LEFT_MARK: usize = RIGHT_MARK << ($n / 2 + 1);
-            const ENGINE_CONFIG: GeneralPurposeConfig = GeneralPurposeConfig::new()
-                .with_decode_padding_mode(DecodePaddingMode::Indifferent);
-            const ENGINE: GeneralPurpose = GeneralPurpose::new(&STANDARD, ENGINE_CONFIG);
+            // The lengths of the Base64 data, without and with padding.
+            const BYTES: usize = (2 << $n) / 8;
+            const UNPADDED: usize = (BYTES * 4).div_ceil(3);
+            const PADDED: usize = BYTES.div_ceil(3) * 4;
 
+            let end = input
+                .trim_end_matches(|c: char| c.is_ascii_whitespace())
+                .len();
+            let input = input.trim_matches(|c: char| c.is_ascii_whitespace());
+            let mut start = end - input.len();
+            if input.is_empty() {
+                return Err(crate::ParseError::at(ParseRuleError::EmptyInput, end));
+            }
+            let (base, variant) = crate::Variant::split(input);
+            if variant != crate::Variant::None {
+                let e = ParseRuleError::VariantInGen;
+                return Err(crate::ParseError::at(e, start + base.len()));
+            }
             let mut gen = 2;
+            // The offset of the number of states, for errors about it.
+            let mut gen_offset = start;
+            // The number of states may also come first, as in Catagolue, e.g., `g3MAP...`.
+            let mut prefixed = false;
+            let mut input = input;
+            if let Some(rest) = input.strip_prefix(['g', 'G']) {
+                let len = rest
+                    .find(|c: char| !c.is_ascii_digit())
+                    .unwrap_or(rest.len());
+                if len > 0
+                    && rest[len..]
+                        .get(..3)
+                        .is_some_and(|prefix| prefix.eq_ignore_ascii_case("MAP"))
+                {
+                    Self::check_case(input.chars().next().unwrap(), opts)
+                        .map_err(|e| crate::ParseError::at(e, start))?;
+                    gen_offset = start + 1;
+                    gen = Self::parse_num(&mut rest[..len].chars().peekable())
+                        .map_err(|e| crate::ParseError::at(e, gen_offset))?;
+                    input = &rest[len..];
+                    start = gen_offset + len;
+                    prefixed = true;
+                }
+            }
             let mut slash = input.len();
-            if !input.starts_with("MAP") {
-                return Err(ParseRuleError::NotMapRule);
+            if !input
+                .get(..3)
+                .is_some_and(|prefix| prefix.eq_ignore_ascii_case("MAP"))
+            {
+                return Err(crate::ParseError::at(ParseRuleError::NotMapRule, start));
             }
-            if let Some(n) = input.rfind('/') {
-                if (n - 3) * 6 >= 2 << $n {
-                    slash = n;
-                    let mut chars = input[n + 1..].chars().peekable();
-                    if chars.peek().is_some() {
-                        gen = Self::parse_num(&mut chars)?;
-                        if chars.next().is_some() {
-                            return Err(ParseRuleError::ExtraJunk);
-                        }
-                    }
+            for (i, c) in input[..3].char_indices() {
+                Self::check_case(c, opts).map_err(|e| crate::ParseError::at(e, start + i))?;
+            }
+            // `/`, `G` and `C` are also Base64 characters, so they only start the number
+            // of states when they immediately follow data of the right length,
+            // not counting whitespace.
+            if let Some(n) = input[3..]
+                .char_indices()
+                .filter(|(_, c)| !c.is_ascii_whitespace())
+                .enumerate()
+                .find(|&(len, (_, c))| "/GgCc".contains(c) && (len == UNPADDED || len == PADDED))
+                .map(|(_, (i, _))| i + 3)
+            {
+                let mut chars = input[n..].chars().peekable();
+                if prefixed {
+                    let found = chars.next().unwrap();
+                    let e = Self::extra_junk(found, chars, end);
+                    return Err(crate::ParseError::at(e, end));
+                }
+                slash = n;
+                chars.next_if_eq(&'/');
+                if let Some(&c) = chars.peek().filter(|c| "GgCc".contains(**c)) {
+                    Self::check_case(c, opts).map_err(|e| Self::error_at(e, chars.clone(), end))?;
+                    chars.next();
+                }
+                gen_offset = end - chars.clone().map(char::len_utf8).sum::<usize>();
+                gen = Self::parse_num(&mut chars)
+                    .map_err(|e| Self::error_at(e, chars.clone(), end))?;
+                if let Some(c) = chars.next() {
+                    let e = Self::extra_junk(c, chars, end);
+                    return Err(crate::ParseError::at(e, end));
+                }
+            }
+            if gen < 2 {
+                if !opts.lenient {
+                    return Err(crate::ParseError::at(
+                        ParseRuleError::GenLessThan2,
+                        gen_offset,
+                    ));
                 }
+                gen = 2;
             }
-            let bytes = ENGINE
-                .decode(&input[3..slash])
-                .map_err(|_| ParseRuleError::Base64Error)?;
+            let data = &input[3..slash];
+            let bytes = crate::map::decode(data).map_err(|e| {
+                let offset = start + 3 + crate::map::error_offset(data, &e);
+                crate::ParseError::at(e, offset)
+            })?;
             if bytes.len() * 8 != 2 << $n {
-                return Err(ParseRuleError::InvalidLength);
+                let e = ParseRuleError::InvalidLength {
+                    expected: 2 << $n,
+                    found: bytes.len() * 8,
+                };
+                return Err(crate::ParseError::at(e, start + 3));
             }
             let mut b = Vec::new();
             let mut s = Vec::new();
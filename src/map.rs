@@ -0,0 +1,210 @@
+//! MAP strings.
+
+use crate::{MapDecodeError, ParseRuleError};
+use base64::{
+    alphabet::{STANDARD, URL_SAFE},
+    engine::{
+        general_purpose::{GeneralPurpose, GeneralPurposeConfig},
+        DecodePaddingMode, Engine,
+    },
+    DecodeError,
+};
+
+const ENGINE_CONFIG: GeneralPurposeConfig =
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent);
+const ENGINE: GeneralPurpose = GeneralPurpose::new(&STANDARD, ENGINE_CONFIG);
+const URL_SAFE_ENGINE: GeneralPurpose = GeneralPurpose::new(&URL_SAFE, ENGINE_CONFIG);
+
+/// Decodes the Base64 data of a MAP string, with or without padding.
+///
+/// ASCII whitespace is removed before decoding, so that MAP strings wrapped
+/// across lines are accepted. Offsets in the error ignore the removed whitespace.
+///
+/// The URL-safe alphabet, with `-` and `_` in place of `+` and `/`,
+/// is tried when the standard alphabet fails.
+/// If both fail, the error from the standard alphabet is returned.
+pub(crate) fn decode(data: &str) -> Result<Vec<u8>, ParseRuleError> {
+    let data: String = data.chars().filter(|c| !c.is_ascii_whitespace()).collect();
+    ENGINE
+        .decode(&data)
+        .or_else(|e| URL_SAFE_ENGINE.decode(&data).map_err(|_| e))
+        .map_err(|e| {
+            ParseRuleError::Base64Error(match e {
+                DecodeError::InvalidByte(i, byte) => MapDecodeError::InvalidByte(i, byte),
+                DecodeError::InvalidLength => MapDecodeError::InvalidLength,
+                DecodeError::InvalidLastSymbol(i, byte) => {
+                    MapDecodeError::InvalidLastSymbol(i, byte)
+                }
+                DecodeError::InvalidPadding => MapDecodeError::InvalidPadding,
+            })
+        })
+}
+
+/// The byte offset in `data` of the character at which [`decode`] failed,
+/// counting the removed whitespace, or `0` if the error is not about a single character.
+pub(crate) fn error_offset(data: &str, e: &ParseRuleError) -> usize {
+    match e {
+        ParseRuleError::Base64Error(
+            MapDecodeError::InvalidByte(i, _) | MapDecodeError::InvalidLastSymbol(i, _),
+        ) => data
+            .char_indices()
+            .filter(|(_, c)| !c.is_ascii_whitespace())
+            .nth(*i)
+            .map_or(0, |(j, _)| j),
+        _ => 0,
+    }
+}
+
+/// Detects the size of the neighborhood of a MAP string from the length of its data,
+/// counting the center cell.
+///
+/// The result is `9` for the Moore neighborhood, `7` for the hexagonal neighborhood,
+/// and `5` for the von Neumann neighborhood, so that the right parser can be chosen
+/// before parsing. A Generations suffix such as `/3` or `/G3`,
+/// or a Catagolue-style prefix such as `g3`, is allowed.
+///
+/// If the length matches none of them, [`ParseRuleError::UnknownMapLength`] is returned
+/// with the number of bits decoded from the whole data.
+///
+/// # Examples
+///
+/// ```
+/// use ca_rules::{map_neighborhood_size, ParseRuleError};
+///
+/// assert_eq!(
+///     map_neighborhood_size(
+///         "MAPARYXfhZofugWaH7oaIDogBZofuhogOiAaIDogIAAgAAWaH7oaIDogGiA6ICAAIAAaIDogIAAgACAAIAAAAAAAA"
+///     ),
+///     Ok(9)
+/// );
+/// assert_eq!(map_neighborhood_size("MAPFgFoF2gXgH5oF4B+gH4A6A"), Ok(7));
+/// assert_eq!(map_neighborhood_size("MAPHmlphg/3"), Ok(5));
+/// assert_eq!(
+///     map_neighborhood_size("MAPHmlphgA"),
+///     Err(ParseRuleError::UnknownMapLength { found: 40 })
+/// );
+/// assert_eq!(map_neighborhood_size("B3/S23"), Err(ParseRuleError::NotMapRule));
+/// ```
+pub fn map_neighborhood_size(input: &str) -> Result<usize, ParseRuleError> {
+    fn size(bits: usize) -> Option<usize> {
+        match bits {
+            0x200 => Some(9),
+            0x80 => Some(7),
+            0x20 => Some(5),
+            _ => None,
+        }
+    }
+
+    /// Whether `suffix` is a Generations suffix, e.g., `/3`, `G3` or `/C3`.
+    fn is_gen_suffix(suffix: &str) -> bool {
+        let suffix = suffix.strip_prefix('/').unwrap_or(suffix);
+        let digits = suffix.strip_prefix(['G', 'g', 'C', 'c']).unwrap_or(suffix);
+        !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit())
+    }
+
+    let input = input.trim_matches(|c: char| c.is_ascii_whitespace());
+    if input.is_empty() {
+        return Err(ParseRuleError::EmptyInput);
+    }
+    // Skip a Catagolue-style number of states, e.g., `g3MAP...`.
+    let input = input
+        .strip_prefix(['g', 'G'])
+        .map(|rest| rest.trim_start_matches(|c: char| c.is_ascii_digit()))
+        .filter(|rest| rest.len() < input.len() - 1)
+        .unwrap_or(input);
+    let payload = input
+        .get(..3)
+        .filter(|prefix| prefix.eq_ignore_ascii_case("MAP"))
+        .map(|_| &input[3..])
+        .ok_or(ParseRuleError::NotMapRule)?;
+    let bytes = decode(payload);
+    if let Some(n) = bytes.as_ref().ok().and_then(|bytes| size(bytes.len() * 8)) {
+        return Ok(n);
+    }
+    // As in the parsers, `/`, `G` and `C` only start the number of states
+    // when they immediately follow data of the right length, not counting whitespace.
+    for n in [9, 7, 5] {
+        let bytes: usize = (1 << n) / 8;
+        let unpadded = (bytes * 4).div_ceil(3);
+        let padded = bytes.div_ceil(3) * 4;
+        if let Some(i) = payload
+            .char_indices()
+            .filter(|(_, c)| !c.is_ascii_whitespace())
+            .enumerate()
+            .find(|&(len, (_, c))| "/GgCc".contains(c) && (len == unpadded || len == padded))
+            .map(|(_, (i, _))| i)
+        {
+            if is_gen_suffix(&payload[i..])
+                && decode(&payload[..i]).is_ok_and(|bytes| bytes.len() * 8 == 1 << n)
+            {
+                return Ok(n);
+            }
+        }
+    }
+    Err(ParseRuleError::UnknownMapLength {
+        found: bytes?.len() * 8,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error;
+
+    #[test]
+    fn neighborhood_size() {
+        assert_eq!(
+            map_neighborhood_size(
+                "MAPARYXfhZofugWaH7oaIDogBZofuhogOiAaIDogIAAgAAWaH7oaIDogGiA6ICAAIAAaIDogIAAgACAAIAAAAAAAA=="
+            ),
+            Ok(9)
+        );
+        assert_eq!(
+            map_neighborhood_size(
+                "MAPARYBFxZpF38WaRd/aZZ//hZpF39pln/+aZZ//pZp/ukWaRd/aZZ//mmWf/6Waf7paZZ//pZp/umWaf7paZbplg/5"
+            ),
+            Ok(9)
+        );
+        assert_eq!(map_neighborhood_size("MAPFgFoF2gXgH5oF4B+gH4A6A/4"), Ok(7));
+        assert_eq!(map_neighborhood_size(" MAPHmlphg\n"), Ok(5));
+        assert_eq!(map_neighborhood_size("mapHmlphg"), Ok(5));
+        assert_eq!(map_neighborhood_size("MAPFgFoF2gXgH5oF4B-gH4A6A"), Ok(7));
+        assert_eq!(map_neighborhood_size("MAPFgFoF2gXg\nH5oF4B+gH4A6A"), Ok(7));
+        assert_eq!(map_neighborhood_size("MAPHml phg /3"), Ok(5));
+        assert_eq!(map_neighborhood_size("MAPHmlphg/C3"), Ok(5));
+        assert_eq!(map_neighborhood_size("MAPHmlphgC3"), Ok(5));
+        assert_eq!(map_neighborhood_size("MAPHmlphgG3"), Ok(5));
+        assert_eq!(map_neighborhood_size("g3MAPHmlphg"), Ok(5));
+        assert_eq!(
+            map_neighborhood_size("gMAPHmlphg"),
+            Err(ParseRuleError::NotMapRule)
+        );
+        assert_eq!(
+            map_neighborhood_size("MAPHmlphgA"),
+            Err(ParseRuleError::UnknownMapLength { found: 40 })
+        );
+        assert_eq!(
+            map_neighborhood_size("MAPFgFoF2gXgH5oF4B+gH4A6A/"),
+            Err(ParseRuleError::Base64Error(
+                MapDecodeError::InvalidLastSymbol(22, b'/')
+            ))
+        );
+        assert_eq!(
+            map_neighborhood_size("MAPHmlphg/C"),
+            Err(ParseRuleError::UnknownMapLength { found: 48 })
+        );
+        assert_eq!(
+            map_neighborhood_size("MAPHml!phg"),
+            Err(ParseRuleError::Base64Error(MapDecodeError::InvalidByte(
+                3, b'!'
+            )))
+        );
+        let e = map_neighborhood_size("MAPHml!phg").unwrap_err();
+        assert_eq!(
+            e.source().map(ToString::to_string),
+            Some("Invalid byte 33, offset 3.".to_string())
+        );
+        assert_eq!(map_neighborhood_size(""), Err(ParseRuleError::EmptyInput));
+        assert_eq!(map_neighborhood_size("MA"), Err(ParseRuleError::NotMapRule));
+    }
+}
@@ -0,0 +1,87 @@
+//! Options for parsing rule strings.
+
+/// Options for the `parse_rule_with` methods of the parser traits.
+///
+/// The default options are the same as those used by `parse_rule`.
+///
+/// # Examples
+///
+/// ```
+/// use ca_rules::{ParseLife, ParseOptions, ParseRuleError};
+///
+/// struct Rule;
+///
+/// impl ParseLife for Rule {
+///     fn from_bs(_b: Vec<u8>, _s: Vec<u8>) -> Self {
+///         Rule
+///     }
+/// }
+///
+/// let opts = ParseOptions {
+///     case_sensitive: true,
+///     ..ParseOptions::default()
+/// };
+///
+/// assert!(Rule::parse_rule_with("B3/S23", &opts).is_ok());
+/// assert_eq!(
+///     Rule::parse_rule_with("b3s23", &opts).err(),
+///     Some(ParseRuleError::Unexpected('b'))
+/// );
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct ParseOptions {
+    /// Whether keywords must be uppercase.
+    ///
+    /// The keywords are `B`, `S`, the neighborhood suffixes `H` and `V`,
+    /// the letters `G` and `C` before the number of states, and the `MAP` prefix.
+    /// A lowercase keyword is rejected with [`ParseRuleError::Unexpected`](crate::ParseRuleError::Unexpected),
+    /// so the lowercase notation of Catagolue, e.g., `g3b3s23`, is rejected too.
+    ///
+    /// The letters of non-totalistic rules are always lowercase.
+    pub case_sensitive: bool,
+
+    /// Whether a number of states less than 2 is treated as 2,
+    /// as in the `parse_rule_lenient` methods of the Generations parser traits.
+    ///
+    /// This has no effect on the parsers of non-Generations rules.
+    pub lenient: bool,
+
+    /// Whether rule strings in [S/B notation](http://www.conwaylife.com/wiki/Rulestring#S.2FB_notation),
+    /// e.g., `23/3` or `3457/357/5`, are accepted.
+    ///
+    /// If this is `false`, a rule string that is not a MAP string must start with `B`,
+    /// or with `G` or `C` for Generations rules in Catagolue's notation.
+    /// Otherwise it is rejected with [`ParseRuleError::Missing('B')`](crate::ParseRuleError::Missing).
+    pub allow_sb_notation: bool,
+
+    /// Whether the extensions of [LifeViewer](http://www.conwaylife.com/wiki/LifeViewer)
+    /// to the B/S and S/B notations are accepted.
+    ///
+    /// If this is `true`, rule strings that are not MAP strings are rewritten as follows
+    /// before parsing:
+    ///
+    /// * `_` is read as `/`, e.g., `B3_S23` or `23_3`.
+    /// * A number of states starting with `C` or `G` may follow the `b` / `s` data
+    ///   without a `/`, even in S/B notation, e.g., `345/2C4` or `34/2C3H`.
+    /// * The neighborhood suffix may be written as `NM` (Moore), `NH` (hexagonal)
+    ///   or `NN` (von Neumann), in Generations rules too, e.g., `B3/S23NM`,
+    ///   `B2/S34NH/C3` or `B2/S013/C3NN`. `NH` and `NN` are read as `H` and `V`,
+    ///   and `NM` is dropped.
+    ///
+    /// `N`, `C` and `G` must be uppercase here, since lowercase letters may belong to
+    /// non-totalistic rules. Nothing else is accepted, and the rewritten rule string
+    /// must still be valid, so e.g. `B3_S23_` and `B3/S23NW` are rejected.
+    /// MAP strings are not rewritten, since `_` is a Base64 character.
+    pub lifeviewer: bool,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        ParseOptions {
+            case_sensitive: false,
+            lenient: false,
+            allow_sb_notation: true,
+            lifeviewer: false,
+        }
+    }
+}
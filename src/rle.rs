@@ -0,0 +1,202 @@
+//! Rule strings in pattern files.
+
+use crate::{ParseRuleError, Topology};
+
+/// Extracts the rule string from a line of a pattern file.
+///
+/// Two kinds of lines are recognized:
+///
+/// * The header line of an [RLE](http://www.conwaylife.com/wiki/Run_Length_Encoded) file,
+///   e.g., `x = 3, y = 3, rule = B3/S23`. The name of the field is case-insensitive,
+///   and whitespace around the `=` is optional.
+/// * A `#r` or `#R` line, e.g., `#r 23/3`, as used in
+///   [Life 1.05](http://www.conwaylife.com/wiki/Life_1.05) files.
+///
+/// The rule string ends at the first whitespace or `#`, so trailing comments are ignored.
+/// Any bounded grid specification is kept as part of the rule string.
+///
+/// # Examples
+///
+/// ```
+/// use ca_rules::extract_rule;
+///
+/// assert_eq!(extract_rule("x = 3, y = 3, rule = B3/S23"), Some("B3/S23"));
+/// assert_eq!(extract_rule("x=3,y=3,RULE=B3/S23:T10,10"), Some("B3/S23:T10,10"));
+/// assert_eq!(extract_rule("#r 23/3"), Some("23/3"));
+/// assert_eq!(extract_rule("x = 3, y = 3"), None);
+/// ```
+pub fn extract_rule(line: &str) -> Option<&str> {
+    let line = line.trim_matches(|c: char| c.is_ascii_whitespace());
+    let value = match line.strip_prefix("#r").or_else(|| line.strip_prefix("#R")) {
+        Some(rest) if rest.starts_with(|c: char| c.is_ascii_whitespace()) => rest,
+        Some(_) => return None,
+        None if line.starts_with('#') => return None,
+        None => rule_field(line)?,
+    };
+    let value = value.trim_start_matches(|c: char| c.is_ascii_whitespace());
+    let end = value
+        .find(|c: char| c.is_ascii_whitespace() || c == '#')
+        .unwrap_or(value.len());
+    Some(&value[..end]).filter(|rule| !rule.is_empty())
+}
+
+/// Finds the value of the `rule` field in an RLE header line.
+///
+/// The value extends to the end of the line,
+/// since bounded grid specifications can contain commas.
+fn rule_field(line: &str) -> Option<&str> {
+    let mut start = 0;
+    loop {
+        let field = line[start..].trim_start_matches(|c: char| c.is_ascii_whitespace());
+        if let Some(rest) = field
+            .get(..4)
+            .filter(|key| key.eq_ignore_ascii_case("rule"))
+            .and_then(|_| {
+                field[4..]
+                    .trim_start_matches(|c: char| c.is_ascii_whitespace())
+                    .strip_prefix('=')
+            })
+        {
+            return Some(rest);
+        }
+        start += line[start..].find(',')? + 1;
+    }
+}
+
+/// Whether the line is an RLE header line, i.e., starts with an `x` field.
+fn is_header(line: &str) -> bool {
+    line.trim_start_matches(|c: char| c.is_ascii_whitespace())
+        .strip_prefix(|c| c == 'x' || c == 'X')
+        .is_some_and(|rest| {
+            rest.trim_start_matches(|c: char| c.is_ascii_whitespace())
+                .starts_with('=')
+        })
+}
+
+/// Parses the rule in a line of a pattern file with the given parser.
+///
+/// The rule string is located by [`extract_rule`], and any bounded grid specification
+/// is stripped before parsing. An RLE header line, i.e., a line starting with an `x` field,
+/// without a `rule` field means Conway's Game of Life, so `B3/S23` is parsed in that case.
+/// Any other line without a rule string is rejected with
+/// [`ParseRuleError::NotRleHeader`].
+///
+/// # Examples
+///
+/// ```
+/// use ca_rules::{parse_rle_header, ParseLife, ParseRuleError};
+///
+/// #[derive(Debug, Eq, PartialEq)]
+/// struct Rule {
+///     b: Vec<u8>,
+///     s: Vec<u8>,
+/// }
+///
+/// impl ParseLife for Rule {
+///     fn from_bs(b: Vec<u8>, s: Vec<u8>) -> Self {
+///         Rule { b, s }
+///     }
+/// }
+///
+/// let rule = parse_rle_header("x = 10, y = 5, rule = B36/S23:T100,100", Rule::parse_rule).unwrap();
+/// assert_eq!(rule, Rule::parse_rule("B36/S23").unwrap());
+///
+/// let rule = parse_rle_header("x = 10, y = 5", Rule::parse_rule).unwrap();
+/// assert_eq!(rule, Rule::parse_rule("B3/S23").unwrap());
+///
+/// assert_eq!(
+///     parse_rle_header("#C A comment", Rule::parse_rule),
+///     Err(ParseRuleError::NotRleHeader)
+/// );
+/// ```
+pub fn parse_rle_header<T, F>(line: &str, parser: F) -> Result<T, ParseRuleError>
+where
+    F: FnOnce(&str) -> Result<T, ParseRuleError>,
+{
+    let rule = match extract_rule(line) {
+        Some(rule) => rule,
+        None if is_header(line) => "B3/S23",
+        None => return Err(ParseRuleError::NotRleHeader),
+    };
+    let (rule, _) = Topology::split(rule)?;
+    parser(rule)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ParseLifeGen;
+
+    #[derive(Debug, Eq, PartialEq)]
+    struct Rule {
+        b: Vec<u8>,
+        s: Vec<u8>,
+        gen: usize,
+    }
+
+    impl ParseLifeGen for Rule {
+        fn from_bsg(b: Vec<u8>, s: Vec<u8>, gen: usize) -> Self {
+            Rule { b, s, gen }
+        }
+    }
+
+    #[test]
+    fn extract() {
+        assert_eq!(extract_rule("x = 3, y = 3, rule = B3/S23"), Some("B3/S23"));
+        assert_eq!(
+            extract_rule("x = 3, y = 3, Rule=B3/S23\r\n"),
+            Some("B3/S23")
+        );
+        assert_eq!(
+            extract_rule("x = 3, y = 3, rule = B3/S23:T10,10 # a torus"),
+            Some("B3/S23:T10,10")
+        );
+        assert_eq!(
+            extract_rule("x = 3, y = 3, rule = B3/S23#comment"),
+            Some("B3/S23")
+        );
+        assert_eq!(extract_rule("#r 23/3"), Some("23/3"));
+        assert_eq!(extract_rule("#R  23/3  "), Some("23/3"));
+        assert_eq!(extract_rule("x = 3, y = 3"), None);
+        assert_eq!(extract_rule("x = 3, y = 3, rule ="), None);
+        assert_eq!(extract_rule("x = 3, y = 3, rules = B3/S23"), None);
+        assert_eq!(extract_rule("#CXRLE Pos=0,-1 Gen=34"), None);
+        assert_eq!(extract_rule("#rule = B3/S23"), None);
+        assert_eq!(extract_rule("#C rule = B3/S23"), None);
+    }
+
+    #[test]
+    fn rle_header() -> Result<(), ParseRuleError> {
+        assert_eq!(
+            parse_rle_header("x = 3, y = 3, rule = 3457/357/5", Rule::parse_rule)?,
+            Rule::parse_rule("3457/357/5")?
+        );
+        assert_eq!(
+            parse_rle_header("x = 3, y = 3, rule = g4b2s:P20,20", Rule::parse_rule)?.gen,
+            4
+        );
+        assert_eq!(
+            parse_rle_header("x = 3, y = 3", Rule::parse_rule)?,
+            Rule::parse_rule("B3/S23")?
+        );
+        assert_eq!(
+            parse_rle_header("X=3,Y=3", Rule::parse_rule)?,
+            Rule::parse_rule("B3/S23")?
+        );
+        for line in ["", "#C x = 3, y = 3", "#r", "3o$obo$3o!", "xy = 3"] {
+            assert_eq!(
+                parse_rle_header(line, Rule::parse_rule).err(),
+                Some(ParseRuleError::NotRleHeader)
+            );
+        }
+        assert_eq!(
+            parse_rle_header("x = 3, y = 3, rule = B3/S23:X", Rule::parse_rule).err(),
+            Some(ParseRuleError::InvalidTopology)
+        );
+        assert_eq!(
+            parse_rle_header("x = 3, y = 3, rule = B3/S2x", Rule::parse_rule).err(),
+            Some(ParseRuleError::extra_junk('x', 5))
+        );
+        Ok(())
+    }
+}
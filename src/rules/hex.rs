@@ -1,7 +1,7 @@
 //! Totalistic hexagonal rules.
 
 use super::Gen;
-use crate::ParseRuleError;
+use crate::{ParseError, ParseOptions, ParseRuleError};
 
 rule_struct!(Hex);
 
@@ -51,7 +51,31 @@ pub trait ParseHex {
     where
         Self: Sized,
     {
-        let Hex { b, s } = Hex::parse_rule(input)?;
+        Self::parse_rule_with(input, &ParseOptions::default())
+    }
+
+    /// The parser, with the given options.
+    fn parse_rule_with(input: &str, opts: &ParseOptions) -> Result<Self, ParseRuleError>
+    where
+        Self: Sized,
+    {
+        Self::parse_rule_at_with(input, opts).map_err(|e| e.kind)
+    }
+
+    /// The parser, returning the byte offset of the error.
+    fn parse_rule_at(input: &str) -> Result<Self, ParseError>
+    where
+        Self: Sized,
+    {
+        Self::parse_rule_at_with(input, &ParseOptions::default())
+    }
+
+    /// The parser, with the given options, returning the byte offset of the error.
+    fn parse_rule_at_with(input: &str, opts: &ParseOptions) -> Result<Self, ParseError>
+    where
+        Self: Sized,
+    {
+        let Hex { b, s } = Hex::parse_rule_with(input, opts)?;
         Ok(Self::from_bs(b, s))
     }
 }
@@ -97,34 +121,81 @@ pub trait ParseHexGen {
 
     /// The parser.
     fn parse_rule(input: &str) -> Result<Self, ParseRuleError>
+    where
+        Self: Sized,
+    {
+        Self::parse_rule_with(input, &ParseOptions::default())
+    }
+
+    /// The parser, with the given options.
+    fn parse_rule_with(input: &str, opts: &ParseOptions) -> Result<Self, ParseRuleError>
+    where
+        Self: Sized,
+    {
+        Self::parse_rule_at_with(input, opts).map_err(|e| e.kind)
+    }
+
+    /// The parser, returning the byte offset of the error.
+    fn parse_rule_at(input: &str) -> Result<Self, ParseError>
+    where
+        Self: Sized,
+    {
+        Self::parse_rule_at_with(input, &ParseOptions::default())
+    }
+
+    /// The parser, with the given options, returning the byte offset of the error.
+    fn parse_rule_at_with(input: &str, opts: &ParseOptions) -> Result<Self, ParseError>
     where
         Self: Sized,
     {
         let Gen {
             rule: Hex { b, s },
             gen,
-        } = Hex::parse_rule_gen(input)?;
+        } = Hex::parse_rule_gen_with(input, opts)?;
         Ok(Self::from_bsg(b, s, gen))
     }
+
+    /// A lenient parser that treats a number of states less than 2 as 2,
+    /// so that strings like `B2/S34H/C1` or `g0b2s34h`, which some programs write
+    /// for rules with two states, are accepted.
+    fn parse_rule_lenient(input: &str) -> Result<Self, ParseRuleError>
+    where
+        Self: Sized,
+    {
+        let opts = ParseOptions {
+            lenient: true,
+            ..ParseOptions::default()
+        };
+        Self::parse_rule_with(input, &opts)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    struct Rule;
+    #[derive(Debug, Eq, PartialEq)]
+    struct Rule {
+        b: Vec<u8>,
+        s: Vec<u8>,
+    }
 
     impl ParseHex for Rule {
-        fn from_bs(_b: Vec<u8>, _s: Vec<u8>) -> Self {
-            Rule
+        fn from_bs(b: Vec<u8>, s: Vec<u8>) -> Self {
+            Rule { b, s }
         }
     }
 
-    struct GenRule;
+    #[derive(Debug, Eq, PartialEq)]
+    struct GenRule {
+        b: Vec<u8>,
+        s: Vec<u8>,
+        gen: usize,
+    }
 
     impl ParseHexGen for GenRule {
-        fn from_bsg(_b: Vec<u8>, _s: Vec<u8>, _gen: usize) -> Self {
-            GenRule
+        fn from_bsg(b: Vec<u8>, s: Vec<u8>, gen: usize) -> Self {
+            GenRule { b, s, gen }
         }
     }
 
@@ -134,6 +205,7 @@ mod tests {
         Rule::parse_rule("B3S23H")?;
         Rule::parse_rule("b3s23h")?;
         Rule::parse_rule("23/3H")?;
+        Rule::parse_rule("B2H")?;
         Rule::parse_rule("23/h")?;
         Ok(())
     }
@@ -142,7 +214,7 @@ mod tests {
     fn invalid_rules() {
         assert_eq!(
             Rule::parse_rule("B3/S23ha").err(),
-            Some(ParseRuleError::ExtraJunk)
+            Some(ParseRuleError::extra_junk('a', 7))
         );
         assert_eq!(
             Rule::parse_rule("B3H/S23").err(),
@@ -154,12 +226,24 @@ mod tests {
         );
         assert_eq!(
             Rule::parse_rule("B3/S27H").err(),
-            Some(ParseRuleError::Missing('H'))
+            Some(ParseRuleError::CountTooLarge(7))
         );
         assert_eq!(
             Rule::parse_rule("233h").err(),
             Some(ParseRuleError::Missing('/'))
         );
+        assert_eq!(
+            Rule::parse_rule("B2").err(),
+            Some(ParseRuleError::Missing('H'))
+        );
+        assert_eq!(
+            Rule::parse_rule("B2HH").err(),
+            Some(ParseRuleError::Missing('S'))
+        );
+        assert_eq!(
+            Rule::parse_rule("B2/S3-7H").err(),
+            Some(ParseRuleError::CountTooLarge(7))
+        );
     }
 
     #[test]
@@ -192,5 +276,239 @@ mod tests {
             GenRule::parse_rule("2333h").err(),
             Some(ParseRuleError::Missing('/'))
         );
+        assert_eq!(
+            GenRule::parse_rule("B3/S23H/G3H").err(),
+            Some(ParseRuleError::extra_junk('H', 10))
+        );
+        assert_eq!(
+            GenRule::parse_rule("23/3h/3h").err(),
+            Some(ParseRuleError::extra_junk('h', 7))
+        );
+        assert_eq!(
+            GenRule::parse_rule("23/3hh").err(),
+            Some(ParseRuleError::extra_junk('h', 5))
+        );
+        assert_eq!(
+            GenRule::parse_rule("g3b3s23h3").err(),
+            Some(ParseRuleError::extra_junk('3', 8))
+        );
+        assert_eq!(
+            GenRule::parse_rule("hg3b3s23").err(),
+            Some(ParseRuleError::Missing('/'))
+        );
+    }
+
+    #[test]
+    fn empty_rule() -> Result<(), ParseRuleError> {
+        for input in ["B/SH", "/H", "bsh", "BH"] {
+            assert_eq!(
+                Rule::parse_rule(input)?,
+                Rule {
+                    b: vec![],
+                    s: vec![]
+                },
+                "{:?}",
+                input
+            );
+        }
+        for input in ["B/S/C3H", "//3H", "g3bsh"] {
+            assert_eq!(
+                GenRule::parse_rule(input)?,
+                GenRule {
+                    b: vec![],
+                    s: vec![],
+                    gen: 3
+                },
+                "{:?}",
+                input
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn surrounding_whitespace() -> Result<(), ParseRuleError> {
+        for input in [" B2/S34H", "B2/S34H\n", "\t34/2h "] {
+            assert_eq!(
+                Rule::parse_rule(input)?,
+                Rule::parse_rule(input.trim())?,
+                "{:?}",
+                input
+            );
+        }
+        for input in [" B2/S34/C3H\r\n", "\tg3b2s34h "] {
+            assert_eq!(
+                GenRule::parse_rule(input)?,
+                GenRule::parse_rule(input.trim())?,
+                "{:?}",
+                input
+            );
+        }
+        assert_eq!(
+            Rule::parse_rule("B2/S34 H").err(),
+            Some(ParseRuleError::Missing('H'))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn empty_input() {
+        for input in ["", " ", "\t\r\n"] {
+            assert_eq!(
+                Rule::parse_rule(input).err(),
+                Some(ParseRuleError::EmptyInput)
+            );
+            assert_eq!(
+                GenRule::parse_rule(input).err(),
+                Some(ParseRuleError::EmptyInput)
+            );
+            assert_eq!(
+                GenRule::parse_rule_lenient(input).err(),
+                Some(ParseRuleError::EmptyInput)
+            );
+        }
+    }
+
+    #[test]
+    fn trailing_slash() {
+        assert_eq!(
+            Rule::parse_rule("B3/S23H/").err(),
+            Some(ParseRuleError::extra_junk('/', 7))
+        );
+        assert_eq!(
+            Rule::parse_rule("23/3H/").err(),
+            Some(ParseRuleError::extra_junk('/', 5))
+        );
+        assert_eq!(
+            GenRule::parse_rule("B3/S23H/").err(),
+            Some(ParseRuleError::MissingNumber)
+        );
+        assert_eq!(
+            GenRule::parse_rule("23/3H/").err(),
+            Some(ParseRuleError::MissingNumber)
+        );
+        assert_eq!(
+            GenRule::parse_rule("g3b3s23h/").err(),
+            Some(ParseRuleError::extra_junk('/', 8))
+        );
+    }
+
+    #[test]
+    fn case_sensitive() -> Result<(), ParseRuleError> {
+        let opts = ParseOptions {
+            case_sensitive: true,
+            ..ParseOptions::default()
+        };
+        Rule::parse_rule_with("B2/S34H", &opts)?;
+        GenRule::parse_rule_with("B2/S34H/C3", &opts)?;
+        assert_eq!(
+            Rule::parse_rule_with("B2/S34h", &opts).err(),
+            Some(ParseRuleError::Unexpected('h'))
+        );
+        assert_eq!(
+            Rule::parse_rule_with("B2h", &opts).err(),
+            Some(ParseRuleError::Unexpected('h'))
+        );
+        assert_eq!(
+            GenRule::parse_rule_with("34/2h3", &opts).err(),
+            Some(ParseRuleError::Unexpected('h'))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn sb_notation() -> Result<(), ParseRuleError> {
+        let opts = ParseOptions {
+            allow_sb_notation: false,
+            ..ParseOptions::default()
+        };
+        Rule::parse_rule_with("B2/S34H", &opts)?;
+        GenRule::parse_rule_with("B2/S34/C3H", &opts)?;
+        assert_eq!(
+            Rule::parse_rule_with("34/2H", &opts).err(),
+            Some(ParseRuleError::Missing('B'))
+        );
+        assert_eq!(
+            GenRule::parse_rule_with("34/2/3H", &opts).err(),
+            Some(ParseRuleError::Missing('B'))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn suffix_order_gen() -> Result<(), ParseRuleError> {
+        let rule = GenRule::parse_rule("B2/S34/C3H")?;
+        for input in [
+            "B2/S34/3H",
+            "B2/S34C3H",
+            "B2/S34H/C3",
+            "B2/S34H/3",
+            "B2/S34HC3",
+            "B2/S34H3",
+            "b2s34hg3",
+            "34/2/3H",
+            "34/2/C3H",
+            "34/2H/3",
+            "34/2H/G3",
+            "34/2H3",
+            "34/2h3",
+            "g3b2s34h",
+        ] {
+            assert_eq!(GenRule::parse_rule(input)?, rule, "{}", input);
+        }
+        assert_eq!(
+            GenRule::parse_rule("23/3/CH").err(),
+            Some(ParseRuleError::MissingNumber)
+        );
+        assert_eq!(
+            GenRule::parse_rule("23/3H/CH").err(),
+            Some(ParseRuleError::MissingNumber)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn error_offsets() {
+        let at = ParseError::at;
+        assert_eq!(
+            Rule::parse_rule_at(" B2/S34 ").err(),
+            Some(at(ParseRuleError::Missing('H'), 7))
+        );
+        assert_eq!(
+            Rule::parse_rule_at("B2/S37H").err(),
+            Some(at(ParseRuleError::CountTooLarge(7), 5))
+        );
+        assert_eq!(
+            GenRule::parse_rule_at("B2/S34/3").err(),
+            Some(at(ParseRuleError::Missing('H'), 8))
+        );
+    }
+
+    #[test]
+    fn lifeviewer() -> Result<(), ParseRuleError> {
+        let opts = ParseOptions {
+            lifeviewer: true,
+            ..ParseOptions::default()
+        };
+        assert_eq!(
+            Rule::parse_rule_with("B2_S34NH", &opts)?,
+            Rule::parse_rule("B2/S34H")?
+        );
+        assert!(Rule::parse_rule("B2_S34NH").is_err());
+        for (input, strict) in [("34/2C3H", "34/2/C3H"), ("B2/S34NH/C3", "B2/S34H/C3")] {
+            assert_eq!(
+                GenRule::parse_rule_with(input, &opts)?,
+                GenRule::parse_rule(strict)?,
+                "{:?}",
+                input
+            );
+            assert!(GenRule::parse_rule(input).is_err(), "{:?}", input);
+        }
+        // Offsets refer to the rule string before it is rewritten.
+        assert_eq!(
+            GenRule::parse_rule_at_with("B2/S34NN/C3", &opts).err(),
+            Some(ParseError::at(ParseRuleError::Missing('H'), 6))
+        );
+        Ok(())
     }
 }
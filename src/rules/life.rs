@@ -1,7 +1,7 @@
 //! Totalistic life-like rules.
 
 use super::Gen;
-use crate::ParseRuleError;
+use crate::{ParseError, ParseOptions, ParseRuleError};
 
 rule_struct!(Life);
 
@@ -51,7 +51,31 @@ pub trait ParseLife {
     where
         Self: Sized,
     {
-        let Life { b, s } = Life::parse_rule(input)?;
+        Self::parse_rule_with(input, &ParseOptions::default())
+    }
+
+    /// The parser, with the given options.
+    fn parse_rule_with(input: &str, opts: &ParseOptions) -> Result<Self, ParseRuleError>
+    where
+        Self: Sized,
+    {
+        Self::parse_rule_at_with(input, opts).map_err(|e| e.kind)
+    }
+
+    /// The parser, returning the byte offset of the error.
+    fn parse_rule_at(input: &str) -> Result<Self, ParseError>
+    where
+        Self: Sized,
+    {
+        Self::parse_rule_at_with(input, &ParseOptions::default())
+    }
+
+    /// The parser, with the given options, returning the byte offset of the error.
+    fn parse_rule_at_with(input: &str, opts: &ParseOptions) -> Result<Self, ParseError>
+    where
+        Self: Sized,
+    {
+        let Life { b, s } = Life::parse_rule_with(input, opts)?;
         Ok(Self::from_bs(b, s))
     }
 }
@@ -97,34 +121,81 @@ pub trait ParseLifeGen {
 
     /// The parser.
     fn parse_rule(input: &str) -> Result<Self, ParseRuleError>
+    where
+        Self: Sized,
+    {
+        Self::parse_rule_with(input, &ParseOptions::default())
+    }
+
+    /// The parser, with the given options.
+    fn parse_rule_with(input: &str, opts: &ParseOptions) -> Result<Self, ParseRuleError>
+    where
+        Self: Sized,
+    {
+        Self::parse_rule_at_with(input, opts).map_err(|e| e.kind)
+    }
+
+    /// The parser, returning the byte offset of the error.
+    fn parse_rule_at(input: &str) -> Result<Self, ParseError>
+    where
+        Self: Sized,
+    {
+        Self::parse_rule_at_with(input, &ParseOptions::default())
+    }
+
+    /// The parser, with the given options, returning the byte offset of the error.
+    fn parse_rule_at_with(input: &str, opts: &ParseOptions) -> Result<Self, ParseError>
     where
         Self: Sized,
     {
         let Gen {
             rule: Life { b, s },
             gen,
-        } = Life::parse_rule_gen(input)?;
+        } = Life::parse_rule_gen_with(input, opts)?;
         Ok(Self::from_bsg(b, s, gen))
     }
+
+    /// A lenient parser that treats a number of states less than 2 as 2,
+    /// so that strings like `B3/S23/C1` or `g0b3s23`, which some programs write
+    /// for rules with two states, are accepted.
+    fn parse_rule_lenient(input: &str) -> Result<Self, ParseRuleError>
+    where
+        Self: Sized,
+    {
+        let opts = ParseOptions {
+            lenient: true,
+            ..ParseOptions::default()
+        };
+        Self::parse_rule_with(input, &opts)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    struct Rule;
+    #[derive(Debug, Eq, PartialEq)]
+    struct Rule {
+        b: Vec<u8>,
+        s: Vec<u8>,
+    }
 
     impl ParseLife for Rule {
-        fn from_bs(_b: Vec<u8>, _s: Vec<u8>) -> Self {
-            Rule
+        fn from_bs(b: Vec<u8>, s: Vec<u8>) -> Self {
+            Rule { b, s }
         }
     }
 
-    struct GenRule;
+    #[derive(Debug, Eq, PartialEq)]
+    struct GenRule {
+        b: Vec<u8>,
+        s: Vec<u8>,
+        gen: usize,
+    }
 
     impl ParseLifeGen for GenRule {
-        fn from_bsg(_b: Vec<u8>, _s: Vec<u8>, _gen: usize) -> Self {
-            GenRule
+        fn from_bsg(b: Vec<u8>, s: Vec<u8>, gen: usize) -> Self {
+            GenRule { b, s, gen }
         }
     }
 
@@ -142,7 +213,7 @@ mod tests {
     fn invalid_rules() {
         assert_eq!(
             Rule::parse_rule("B3/S23h").err(),
-            Some(ParseRuleError::ExtraJunk)
+            Some(ParseRuleError::extra_junk('h', 6))
         );
         assert_eq!(
             Rule::parse_rule("B3/23").err(),
@@ -156,10 +227,112 @@ mod tests {
             Rule::parse_rule("233").err(),
             Some(ParseRuleError::Missing('/'))
         );
+        assert_eq!(
+            Rule::parse_rule("B3/S239").err(),
+            Some(ParseRuleError::CountTooLarge(9))
+        );
+        assert_eq!(Rule::parse_rule("").err(), Some(ParseRuleError::EmptyInput));
+        assert_eq!(
+            Rule::parse_rule(" \n").err(),
+            Some(ParseRuleError::EmptyInput)
+        );
+    }
+
+    #[test]
+    fn birth_only() -> Result<(), ParseRuleError> {
+        let opts = ParseOptions::default();
+        assert_eq!(
+            Life::parse_rule_with("B2", &opts).map_err(|e| e.kind)?,
+            Life::parse_rule_with("B2/S", &opts).map_err(|e| e.kind)?
+        );
+        assert_eq!(
+            Life::parse_rule_with("b34", &opts).map_err(|e| e.kind)?,
+            Life::parse_rule_with("B34/S", &opts).map_err(|e| e.kind)?
+        );
+        assert_eq!(
+            Life::parse_rule_with("B", &opts).map_err(|e| e.kind)?,
+            Life::parse_rule_with("B/S", &opts).map_err(|e| e.kind)?
+        );
+        assert_eq!(
+            Life::parse_rule_with("2", &opts).map_err(|e| e.kind).err(),
+            Some(ParseRuleError::Missing('/'))
+        );
+        assert_eq!(
+            Life::parse_rule_with("B2/", &opts)
+                .map_err(|e| e.kind)
+                .err(),
+            Some(ParseRuleError::Missing('S'))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn ranges() -> Result<(), ParseRuleError> {
+        let opts = ParseOptions::default();
+        assert_eq!(
+            Life::parse_rule_with("B3/S2-5", &opts).map_err(|e| e.kind)?,
+            Life::parse_rule_with("B3/S2345", &opts).map_err(|e| e.kind)?
+        );
+        assert_eq!(
+            Life::parse_rule_with("B3,6/S2,3", &opts).map_err(|e| e.kind)?,
+            Life::parse_rule_with("B36/S23", &opts).map_err(|e| e.kind)?
+        );
+        assert_eq!(
+            Life::parse_rule_with("B0-2,5/S1-3,6-8", &opts).map_err(|e| e.kind)?,
+            Life::parse_rule_with("B0125/S123678", &opts).map_err(|e| e.kind)?
+        );
+        assert_eq!(
+            Life::parse_rule_with("2-3/3", &opts).map_err(|e| e.kind)?,
+            Life::parse_rule_with("23/3", &opts).map_err(|e| e.kind)?
+        );
+        assert_eq!(
+            Life::parse_rule_with("B3/S4-4", &opts).map_err(|e| e.kind)?,
+            Life::parse_rule_with("B3/S4", &opts).map_err(|e| e.kind)?
+        );
+        assert_eq!(
+            Life::parse_rule_with("B33/S2-4,3", &opts).map_err(|e| e.kind)?,
+            Life::parse_rule_with("B3/S234", &opts).map_err(|e| e.kind)?
+        );
+        assert_eq!(
+            Life::parse_rule_gen_with("345/2,4-6/4", &opts).map_err(|e| e.kind)?,
+            Life::parse_rule_gen_with("345/2456/4", &opts).map_err(|e| e.kind)?
+        );
+        assert_eq!(
+            Life::parse_rule_with("B3/S5-2", &opts)
+                .map_err(|e| e.kind)
+                .err(),
+            Some(ParseRuleError::InvalidRange(5, 2))
+        );
+        assert_eq!(
+            Life::parse_rule_with("B3/S2-9", &opts)
+                .map_err(|e| e.kind)
+                .err(),
+            Some(ParseRuleError::CountTooLarge(9))
+        );
+        assert_eq!(
+            Life::parse_rule_with("B3-/S23", &opts)
+                .map_err(|e| e.kind)
+                .err(),
+            Some(ParseRuleError::MissingNumber)
+        );
+        assert_eq!(
+            Life::parse_rule_with("B3,/S23", &opts)
+                .map_err(|e| e.kind)
+                .err(),
+            Some(ParseRuleError::MissingNumber)
+        );
+        assert_eq!(
+            Life::parse_rule_with("B,3/S23", &opts)
+                .map_err(|e| e.kind)
+                .err(),
+            Some(ParseRuleError::Missing('S'))
+        );
+        Ok(())
     }
 
     #[test]
     fn valid_rules_gen() -> Result<(), ParseRuleError> {
+        let opts = ParseOptions::default();
         GenRule::parse_rule("B3/S23/C3")?;
         GenRule::parse_rule("B3S23G3")?;
         GenRule::parse_rule("g3b3s23")?;
@@ -167,6 +340,84 @@ mod tests {
         GenRule::parse_rule("23/3/3")?;
         GenRule::parse_rule("23//3")?;
         GenRule::parse_rule("23/3")?;
+        assert_eq!(
+            Life::parse_rule_gen_with("23/3/C5", &opts)
+                .map_err(|e| e.kind)?
+                .gen,
+            5
+        );
+        assert_eq!(
+            Life::parse_rule_gen_with("23/3/g5", &opts)
+                .map_err(|e| e.kind)?
+                .gen,
+            5
+        );
+        let max = usize::MAX;
+        assert_eq!(
+            Life::parse_rule_gen_with(&format!("23/3/{}", max), &opts)
+                .map_err(|e| e.kind)?
+                .gen,
+            max
+        );
+        assert_eq!(
+            Life::parse_rule_gen_with(&format!("B3/S23/C{}", max), &opts)
+                .map_err(|e| e.kind)?
+                .gen,
+            max
+        );
+        assert_eq!(
+            Life::parse_rule_gen_with(&format!("g{}b3s23", max), &opts)
+                .map_err(|e| e.kind)?
+                .gen,
+            max
+        );
+        // One more than the limit of the target.
+        assert_eq!(
+            Life::parse_rule_gen_with(&format!("23/3/{}", max as u128 + 1), &opts)
+                .map_err(|e| e.kind)
+                .err(),
+            Some(ParseRuleError::GenOverflow)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn lenient_rules_gen() -> Result<(), ParseRuleError> {
+        let lenient = ParseOptions {
+            lenient: true,
+            ..ParseOptions::default()
+        };
+        assert_eq!(
+            Life::parse_rule_gen_with("B3/S23/C1", &lenient)
+                .map_err(|e| e.kind)?
+                .gen,
+            2
+        );
+        assert_eq!(
+            Life::parse_rule_gen_with("g0b3s23", &lenient)
+                .map_err(|e| e.kind)?
+                .gen,
+            2
+        );
+        assert_eq!(
+            Life::parse_rule_gen_with("23/3/5", &lenient)
+                .map_err(|e| e.kind)?
+                .gen,
+            5
+        );
+        GenRule::parse_rule_lenient("23/3/0")?;
+        assert_eq!(
+            GenRule::parse_rule("23/3/0").err(),
+            Some(ParseRuleError::GenLessThan2)
+        );
+        assert_eq!(
+            GenRule::parse_rule_lenient("23/3/C").err(),
+            Some(ParseRuleError::MissingNumber)
+        );
+        assert_eq!(
+            GenRule::parse_rule_lenient("g1b3s23x").err(),
+            Some(ParseRuleError::extra_junk('x', 7))
+        );
         Ok(())
     }
 
@@ -174,7 +425,7 @@ mod tests {
     fn invalid_rules_gen() {
         assert_eq!(
             GenRule::parse_rule("B3/S23h").err(),
-            Some(ParseRuleError::ExtraJunk)
+            Some(ParseRuleError::extra_junk('h', 6))
         );
         assert_eq!(
             GenRule::parse_rule("B3/S23/").err(),
@@ -184,6 +435,14 @@ mod tests {
             GenRule::parse_rule("g1b3s23").err(),
             Some(ParseRuleError::GenLessThan2)
         );
+        assert_eq!(
+            GenRule::parse_rule("23/3/C").err(),
+            Some(ParseRuleError::MissingNumber)
+        );
+        assert_eq!(
+            GenRule::parse_rule("23/3/CG5").err(),
+            Some(ParseRuleError::MissingNumber)
+        );
         assert_eq!(
             GenRule::parse_rule("2333").err(),
             Some(ParseRuleError::Missing('/'))
@@ -192,5 +451,302 @@ mod tests {
             GenRule::parse_rule("23/3/18446744073709551617").err(),
             Some(ParseRuleError::GenOverflow)
         );
+        assert_eq!(
+            GenRule::parse_rule("23/3/99999999999999999999999").err(),
+            Some(ParseRuleError::GenOverflow)
+        );
+        assert_eq!(
+            GenRule::parse_rule("B3/S23/C99999999999999999999999").err(),
+            Some(ParseRuleError::GenOverflow)
+        );
+        assert_eq!(
+            GenRule::parse_rule("g99999999999999999999999b3s23").err(),
+            Some(ParseRuleError::GenOverflow)
+        );
+        assert_eq!(
+            GenRule::parse_rule("").err(),
+            Some(ParseRuleError::EmptyInput)
+        );
+        assert_eq!(
+            GenRule::parse_rule("B3/S23History").err(),
+            Some(ParseRuleError::VariantInGen)
+        );
+        assert_eq!(
+            GenRule::parse_rule("g3b3s23super").err(),
+            Some(ParseRuleError::VariantInGen)
+        );
+    }
+
+    #[test]
+    fn empty_rule() -> Result<(), ParseRuleError> {
+        for input in ["B/S", "/", "bs", "B"] {
+            assert_eq!(
+                Rule::parse_rule(input)?,
+                Rule {
+                    b: vec![],
+                    s: vec![]
+                },
+                "{:?}",
+                input
+            );
+        }
+        for input in ["B/S/C3", "//3", "g3bs"] {
+            assert_eq!(
+                GenRule::parse_rule(input)?,
+                GenRule {
+                    b: vec![],
+                    s: vec![],
+                    gen: 3
+                },
+                "{:?}",
+                input
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn surrounding_whitespace() -> Result<(), ParseRuleError> {
+        for input in [" B3/S23", "B3/S23 ", "\tB3/S23\n", "  23/3\t"] {
+            assert_eq!(
+                Rule::parse_rule(input)?,
+                Rule::parse_rule(input.trim())?,
+                "{:?}",
+                input
+            );
+        }
+        for input in [" 3457/357/5\n", "\tg3b3s23 "] {
+            assert_eq!(
+                GenRule::parse_rule(input)?,
+                GenRule::parse_rule(input.trim())?,
+                "{:?}",
+                input
+            );
+        }
+        assert_eq!(
+            Rule::parse_rule("B3 /S23").err(),
+            Some(ParseRuleError::Missing('S'))
+        );
+        assert_eq!(
+            Rule::parse_rule("  B3/S23ék\n").err(),
+            Some(ParseRuleError::extra_junk('é', 8))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn empty_input() {
+        for input in ["", " ", "\t\r\n"] {
+            assert_eq!(
+                Rule::parse_rule(input).err(),
+                Some(ParseRuleError::EmptyInput)
+            );
+            assert_eq!(
+                GenRule::parse_rule(input).err(),
+                Some(ParseRuleError::EmptyInput)
+            );
+            assert_eq!(
+                GenRule::parse_rule_lenient(input).err(),
+                Some(ParseRuleError::EmptyInput)
+            );
+        }
+    }
+
+    #[test]
+    fn trailing_slash() {
+        assert_eq!(
+            Rule::parse_rule("B3/S23/").err(),
+            Some(ParseRuleError::extra_junk('/', 6))
+        );
+        assert_eq!(
+            Rule::parse_rule("23/3/").err(),
+            Some(ParseRuleError::extra_junk('/', 4))
+        );
+        assert_eq!(
+            Rule::parse_rule("B/S/").err(),
+            Some(ParseRuleError::extra_junk('/', 3))
+        );
+        assert_eq!(
+            GenRule::parse_rule("B3/S23/").err(),
+            Some(ParseRuleError::MissingNumber)
+        );
+        assert_eq!(
+            GenRule::parse_rule("23/3/").err(),
+            Some(ParseRuleError::MissingNumber)
+        );
+        assert_eq!(
+            GenRule::parse_rule("g3b3s23/").err(),
+            Some(ParseRuleError::extra_junk('/', 7))
+        );
+    }
+
+    #[test]
+    fn case_sensitive() -> Result<(), ParseRuleError> {
+        let opts = ParseOptions {
+            case_sensitive: true,
+            ..ParseOptions::default()
+        };
+        Rule::parse_rule_with("B3/S23", &opts)?;
+        Rule::parse_rule_with("23/3", &opts)?;
+        GenRule::parse_rule_with("B3/S23/C3", &opts)?;
+        GenRule::parse_rule_with("G3/B3/S23", &opts)?;
+        assert_eq!(
+            Rule::parse_rule_with("b3/S23", &opts).err(),
+            Some(ParseRuleError::Unexpected('b'))
+        );
+        assert_eq!(
+            Rule::parse_rule_with("B3s23", &opts).err(),
+            Some(ParseRuleError::Unexpected('s'))
+        );
+        assert_eq!(
+            GenRule::parse_rule_with("B3/S23/c3", &opts).err(),
+            Some(ParseRuleError::Unexpected('c'))
+        );
+        assert_eq!(
+            GenRule::parse_rule_with("g3b3s23", &opts).err(),
+            Some(ParseRuleError::Unexpected('g'))
+        );
+        Rule::parse_rule_with("b3s23", &ParseOptions::default())?;
+        GenRule::parse_rule_with("g3b3s23", &ParseOptions::default())?;
+        Ok(())
+    }
+
+    #[test]
+    fn sb_notation() -> Result<(), ParseRuleError> {
+        let opts = ParseOptions {
+            allow_sb_notation: false,
+            ..ParseOptions::default()
+        };
+        Rule::parse_rule_with("23/3", &ParseOptions::default())?;
+        GenRule::parse_rule_with("23/3/3", &ParseOptions::default())?;
+        Rule::parse_rule_with("B3/S23", &opts)?;
+        GenRule::parse_rule_with("B3/S23/C3", &opts)?;
+        GenRule::parse_rule_with("g3b3s23", &opts)?;
+        assert_eq!(
+            Rule::parse_rule_with("23/3", &opts).err(),
+            Some(ParseRuleError::Missing('B'))
+        );
+        assert_eq!(
+            GenRule::parse_rule_with("23/3", &opts).err(),
+            Some(ParseRuleError::Missing('B'))
+        );
+        assert_eq!(
+            GenRule::parse_rule_with("3457/357/5", &opts).err(),
+            Some(ParseRuleError::Missing('B'))
+        );
+        assert_eq!(
+            Rule::parse_rule_with("S23/B3", &opts).err(),
+            Some(ParseRuleError::Missing('B'))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn error_offsets() {
+        let at = ParseError::at;
+        assert_eq!(
+            Rule::parse_rule_at("").err(),
+            Some(at(ParseRuleError::EmptyInput, 0))
+        );
+        assert_eq!(
+            Rule::parse_rule_at("B3/X23").err(),
+            Some(at(ParseRuleError::Missing('S'), 3))
+        );
+        assert_eq!(
+            Rule::parse_rule_at("233").err(),
+            Some(at(ParseRuleError::Missing('/'), 3))
+        );
+        assert_eq!(
+            Rule::parse_rule_at("B3,/S23").err(),
+            Some(at(ParseRuleError::MissingNumber, 3))
+        );
+        assert_eq!(
+            Rule::parse_rule_at("B39/S23").err(),
+            Some(at(ParseRuleError::CountTooLarge(9), 2))
+        );
+        assert_eq!(
+            Rule::parse_rule_at("B3/S5-2").err(),
+            Some(at(ParseRuleError::InvalidRange(5, 2), 4))
+        );
+        assert_eq!(
+            Rule::parse_rule_at("  B3/S2x3").err(),
+            Some(at(ParseRuleError::extra_junk('x', 7), 7))
+        );
+        assert_eq!(
+            GenRule::parse_rule_at("B3/S23/C1").err(),
+            Some(at(ParseRuleError::GenLessThan2, 8))
+        );
+        assert_eq!(
+            GenRule::parse_rule_at("g1b3s23").err(),
+            Some(at(ParseRuleError::GenLessThan2, 1))
+        );
+        assert_eq!(
+            GenRule::parse_rule_at("B3/S23/C99999999999999999999999").err(),
+            Some(at(ParseRuleError::GenOverflow, 8))
+        );
+        assert_eq!(
+            GenRule::parse_rule_at("B3/S23History").err(),
+            Some(at(ParseRuleError::VariantInGen, 6))
+        );
+    }
+
+    #[test]
+    fn lifeviewer() -> Result<(), ParseRuleError> {
+        let opts = ParseOptions {
+            lifeviewer: true,
+            ..ParseOptions::default()
+        };
+        for (input, strict) in [
+            ("B3_S23", "B3/S23"),
+            ("23_3", "23/3"),
+            ("B3/S23NM", "B3/S23"),
+        ] {
+            assert_eq!(
+                Rule::parse_rule_with(input, &opts)?,
+                Rule::parse_rule(strict)?,
+                "{:?}",
+                input
+            );
+            assert!(Rule::parse_rule(input).is_err(), "{:?}", input);
+        }
+        for (input, strict) in [
+            ("345_2_4", "345/2/4"),
+            ("345/2C4", "345/2/C4"),
+            ("B2_S345G4NM", "B2/S345/G4"),
+        ] {
+            assert_eq!(
+                GenRule::parse_rule_with(input, &opts)?,
+                GenRule::parse_rule(strict)?,
+                "{:?}",
+                input
+            );
+            assert!(GenRule::parse_rule(input).is_err(), "{:?}", input);
+        }
+        assert_eq!(
+            GenRule::parse_rule_with("B3_S23_", &opts).err(),
+            Some(ParseRuleError::MissingNumber)
+        );
+        assert_eq!(
+            GenRule::parse_rule_with("345/2c4", &opts).err(),
+            Some(ParseRuleError::extra_junk('c', 5))
+        );
+        for input in ["B3/S23NW", "B3/S23NH"] {
+            assert_eq!(
+                GenRule::parse_rule_with(input, &opts).err(),
+                Some(ParseRuleError::extra_junk('N', 6)),
+                "{:?}",
+                input
+            );
+        }
+        assert_eq!(
+            GenRule::parse_rule_with("B3/S23nm", &opts).err(),
+            Some(ParseRuleError::extra_junk('n', 6))
+        );
+        // Offsets refer to the rule string before it is rewritten.
+        assert_eq!(
+            Rule::parse_rule_at_with("B3_S23NMx", &opts).err(),
+            Some(ParseError::at(ParseRuleError::extra_junk('x', 8), 8))
+        );
+        Ok(())
     }
 }
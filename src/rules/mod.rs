@@ -10,8 +10,8 @@ mod ntneumann;
 pub use hex::{ParseHex, ParseHexGen};
 pub use life::{ParseLife, ParseLifeGen};
 pub use neumann::{ParseNeumann, ParseNeumannGen};
-pub use nthex::{ParseNtHex, ParseNtHexGen};
-pub use ntlife::{ParseNtLife, ParseNtLifeGen};
+pub use nthex::{ParseNtHex, ParseNtHexGen, NTHEX_NEIGHBOR_MASKS};
+pub use ntlife::{ParseNtLife, ParseNtLifeGen, NTLIFE_NEIGHBOR_MASKS};
 pub use ntneumann::{ParseNtNeumann, ParseNtNeumannGen};
 
 /// A helper struct to represent Generations rules.
@@ -20,3 +20,21 @@ struct Gen<T> {
     rule: T,
     gen: usize,
 }
+
+/// Tries the totalistic parser `parser` before a non-totalistic one, so that plain
+/// totalistic strings mean the same in both, e.g., `B3/S23c3`, where `c3` is the number
+/// of states.
+///
+/// This is skipped for ranges and lists of counts, e.g., `B3-4/S23` or `B3,4/S23`,
+/// since `-` starts a negation in non-totalistic rules.
+fn parse_totalistic<T>(
+    input: &str,
+    opts: &crate::ParseOptions,
+    parser: fn(&str, &crate::ParseOptions) -> Result<T, crate::ParseError>,
+) -> Option<T> {
+    if input.contains(['-', ',']) {
+        None
+    } else {
+        parser(input, opts).ok()
+    }
+}
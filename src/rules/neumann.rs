@@ -1,7 +1,7 @@
 //! Totalistic rules with von Neumann neighborhood.
 
 use super::Gen;
-use crate::ParseRuleError;
+use crate::{ParseError, ParseOptions, ParseRuleError};
 
 rule_struct!(Neumann);
 
@@ -52,7 +52,31 @@ pub trait ParseNeumann {
     where
         Self: Sized,
     {
-        let Neumann { b, s } = Neumann::parse_rule(input)?;
+        Self::parse_rule_with(input, &ParseOptions::default())
+    }
+
+    /// The parser, with the given options.
+    fn parse_rule_with(input: &str, opts: &ParseOptions) -> Result<Self, ParseRuleError>
+    where
+        Self: Sized,
+    {
+        Self::parse_rule_at_with(input, opts).map_err(|e| e.kind)
+    }
+
+    /// The parser, returning the byte offset of the error.
+    fn parse_rule_at(input: &str) -> Result<Self, ParseError>
+    where
+        Self: Sized,
+    {
+        Self::parse_rule_at_with(input, &ParseOptions::default())
+    }
+
+    /// The parser, with the given options, returning the byte offset of the error.
+    fn parse_rule_at_with(input: &str, opts: &ParseOptions) -> Result<Self, ParseError>
+    where
+        Self: Sized,
+    {
+        let Neumann { b, s } = Neumann::parse_rule_with(input, opts)?;
         Ok(Self::from_bs(b, s))
     }
 }
@@ -98,26 +122,81 @@ pub trait ParseNeumannGen {
 
     /// The parser.
     fn parse_rule(input: &str) -> Result<Self, ParseRuleError>
+    where
+        Self: Sized,
+    {
+        Self::parse_rule_with(input, &ParseOptions::default())
+    }
+
+    /// The parser, with the given options.
+    fn parse_rule_with(input: &str, opts: &ParseOptions) -> Result<Self, ParseRuleError>
+    where
+        Self: Sized,
+    {
+        Self::parse_rule_at_with(input, opts).map_err(|e| e.kind)
+    }
+
+    /// The parser, returning the byte offset of the error.
+    fn parse_rule_at(input: &str) -> Result<Self, ParseError>
+    where
+        Self: Sized,
+    {
+        Self::parse_rule_at_with(input, &ParseOptions::default())
+    }
+
+    /// The parser, with the given options, returning the byte offset of the error.
+    fn parse_rule_at_with(input: &str, opts: &ParseOptions) -> Result<Self, ParseError>
     where
         Self: Sized,
     {
         let Gen {
             rule: Neumann { b, s },
             gen,
-        } = Neumann::parse_rule_gen(input)?;
+        } = Neumann::parse_rule_gen_with(input, opts)?;
         Ok(Self::from_bsg(b, s, gen))
     }
+
+    /// A lenient parser that treats a number of states less than 2 as 2,
+    /// so that strings like `B2/S013V/C1` or `g0b2s013v`, which some programs write
+    /// for rules with two states, are accepted.
+    fn parse_rule_lenient(input: &str) -> Result<Self, ParseRuleError>
+    where
+        Self: Sized,
+    {
+        let opts = ParseOptions {
+            lenient: true,
+            ..ParseOptions::default()
+        };
+        Self::parse_rule_with(input, &opts)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    struct Rule;
+    #[derive(Debug, Eq, PartialEq)]
+    struct Rule {
+        b: Vec<u8>,
+        s: Vec<u8>,
+    }
 
     impl ParseNeumann for Rule {
-        fn from_bs(_b: Vec<u8>, _s: Vec<u8>) -> Self {
-            Rule
+        fn from_bs(b: Vec<u8>, s: Vec<u8>) -> Self {
+            Rule { b, s }
+        }
+    }
+
+    #[derive(Debug, Eq, PartialEq)]
+    struct GenRule {
+        b: Vec<u8>,
+        s: Vec<u8>,
+        gen: usize,
+    }
+
+    impl ParseNeumannGen for GenRule {
+        fn from_bsg(b: Vec<u8>, s: Vec<u8>, gen: usize) -> Self {
+            GenRule { b, s, gen }
         }
     }
 
@@ -135,7 +214,7 @@ mod tests {
     fn invalid_rules() {
         assert_eq!(
             Rule::parse_rule("B3/S23va").err(),
-            Some(ParseRuleError::ExtraJunk)
+            Some(ParseRuleError::extra_junk('a', 7))
         );
         assert_eq!(
             Rule::parse_rule("B3V/S23").err(),
@@ -147,11 +226,201 @@ mod tests {
         );
         assert_eq!(
             Rule::parse_rule("B3/S25V").err(),
-            Some(ParseRuleError::Missing('V'))
+            Some(ParseRuleError::CountTooLarge(5))
         );
         assert_eq!(
             Rule::parse_rule("233v").err(),
             Some(ParseRuleError::Missing('/'))
         );
     }
+
+    #[test]
+    fn empty_rule() -> Result<(), ParseRuleError> {
+        for input in ["B/SV", "/V", "bsv", "BV"] {
+            assert_eq!(
+                Rule::parse_rule(input)?,
+                Rule {
+                    b: vec![],
+                    s: vec![]
+                },
+                "{:?}",
+                input
+            );
+        }
+        for input in ["B/S/C3V", "//3V", "g3bsv"] {
+            assert_eq!(
+                GenRule::parse_rule(input)?,
+                GenRule {
+                    b: vec![],
+                    s: vec![],
+                    gen: 3
+                },
+                "{:?}",
+                input
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn surrounding_whitespace() -> Result<(), ParseRuleError> {
+        for input in [" B2/S013V", "B2/S013V\n", "\t013/2v "] {
+            assert_eq!(
+                Rule::parse_rule(input)?,
+                Rule::parse_rule(input.trim())?,
+                "{:?}",
+                input
+            );
+        }
+        for input in [" B2/S013/C3V\r\n", "\tg3b2s013v "] {
+            assert_eq!(
+                GenRule::parse_rule(input)?,
+                GenRule::parse_rule(input.trim())?,
+                "{:?}",
+                input
+            );
+        }
+        assert_eq!(
+            Rule::parse_rule("B2/S013 V").err(),
+            Some(ParseRuleError::Missing('V'))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn empty_input() {
+        for input in ["", " ", "\t\r\n"] {
+            assert_eq!(
+                Rule::parse_rule(input).err(),
+                Some(ParseRuleError::EmptyInput)
+            );
+            assert_eq!(
+                GenRule::parse_rule(input).err(),
+                Some(ParseRuleError::EmptyInput)
+            );
+            assert_eq!(
+                GenRule::parse_rule_lenient(input).err(),
+                Some(ParseRuleError::EmptyInput)
+            );
+        }
+    }
+
+    #[test]
+    fn trailing_slash() {
+        assert_eq!(
+            Rule::parse_rule("B3/S23V/").err(),
+            Some(ParseRuleError::extra_junk('/', 7))
+        );
+        assert_eq!(
+            Rule::parse_rule("23/3V/").err(),
+            Some(ParseRuleError::extra_junk('/', 5))
+        );
+        assert_eq!(
+            GenRule::parse_rule("B3/S23V/").err(),
+            Some(ParseRuleError::MissingNumber)
+        );
+        assert_eq!(
+            GenRule::parse_rule("23/3V/").err(),
+            Some(ParseRuleError::MissingNumber)
+        );
+        assert_eq!(
+            GenRule::parse_rule("g3b3s23v/").err(),
+            Some(ParseRuleError::extra_junk('/', 8))
+        );
+    }
+
+    #[test]
+    fn case_sensitive() -> Result<(), ParseRuleError> {
+        let opts = ParseOptions {
+            case_sensitive: true,
+            ..ParseOptions::default()
+        };
+        Rule::parse_rule_with("B2/S013V", &opts)?;
+        GenRule::parse_rule_with("B2/S013V/C3", &opts)?;
+        assert_eq!(
+            Rule::parse_rule_with("B2/S013v", &opts).err(),
+            Some(ParseRuleError::Unexpected('v'))
+        );
+        assert_eq!(
+            GenRule::parse_rule_with("B2/S013V/g3", &opts).err(),
+            Some(ParseRuleError::Unexpected('g'))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn sb_notation() -> Result<(), ParseRuleError> {
+        let opts = ParseOptions {
+            allow_sb_notation: false,
+            ..ParseOptions::default()
+        };
+        Rule::parse_rule_with("B2/S013V", &opts)?;
+        GenRule::parse_rule_with("B2/S013/C3V", &opts)?;
+        assert_eq!(
+            Rule::parse_rule_with("013/2V", &opts).err(),
+            Some(ParseRuleError::Missing('B'))
+        );
+        assert_eq!(
+            GenRule::parse_rule_with("013/2/3V", &opts).err(),
+            Some(ParseRuleError::Missing('B'))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn suffix_order_gen() -> Result<(), ParseRuleError> {
+        let rule = GenRule::parse_rule("B2/S013/C4V")?;
+        assert_eq!(rule.gen, 4);
+        for input in [
+            "B2/S013/4V",
+            "B2/S013C4V",
+            "B2/S013G4V",
+            "B2/S013V/C4",
+            "B2/S013V/4",
+            "B2/S013VC4",
+            "B2/S013V4",
+            "b2s013vg4",
+            "013/2/4V",
+            "013/2/C4V",
+            "013/2V/4",
+            "013/2V/G4",
+            "013/2V4",
+            "013/2v4",
+            "g4b2s013v",
+        ] {
+            assert_eq!(GenRule::parse_rule(input)?, rule, "{}", input);
+        }
+        assert_eq!(
+            GenRule::parse_rule("013/2V/4V").err(),
+            Some(ParseRuleError::extra_junk('V', 8))
+        );
+        assert_eq!(
+            GenRule::parse_rule("B2/S013V4V").err(),
+            Some(ParseRuleError::extra_junk('V', 9))
+        );
+        assert_eq!(
+            GenRule::parse_rule("B2/S013/4").err(),
+            Some(ParseRuleError::Missing('V'))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn lifeviewer() -> Result<(), ParseRuleError> {
+        let opts = ParseOptions {
+            lifeviewer: true,
+            ..ParseOptions::default()
+        };
+        assert_eq!(
+            Rule::parse_rule_with("B2/S013NN", &opts)?,
+            Rule::parse_rule("B2/S013V")?
+        );
+        assert!(Rule::parse_rule("B2/S013NN").is_err());
+        assert_eq!(
+            GenRule::parse_rule_with("B2/S013/C3NN", &opts)?,
+            GenRule::parse_rule("B2/S013/C3V")?
+        );
+        assert!(GenRule::parse_rule("B2/S013/C3NN").is_err());
+        Ok(())
+    }
 }
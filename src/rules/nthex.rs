@@ -2,44 +2,112 @@
 
 use super::{
     hex::{ParseHex, ParseHexGen},
-    Gen,
+    parse_totalistic, Gen,
 };
-use crate::ParseRuleError;
+use crate::{ParseError, ParseOptions, ParseRuleError};
+
+/// The neighborhoods of each letter in isotropic non-totalistic hexagonal rules.
+///
+/// The table is indexed by the number of neighbors. Each entry lists the letters for that count,
+/// together with the neighborhoods that each letter stands for, represented as in [`ParseNtHex`].
+/// A count without letters, e.g., the `3` in `B2/S34H`, stands for all of them.
+///
+/// # Examples
+///
+/// ```
+/// use ca_rules::NTHEX_NEIGHBOR_MASKS;
+///
+/// let (_, masks) = NTHEX_NEIGHBOR_MASKS[2]
+///     .iter()
+///     .find(|&&(letter, _)| letter == 'p')
+///     .unwrap();
+/// assert_eq!(masks, &[0x0c, 0x12, 0x21]);
+/// ```
+pub const NTHEX_NEIGHBOR_MASKS: [&[(char, &[u8])]; 7] = [
+    &[('o', &[0x00])],
+    &[('o', &[0x01, 0x02, 0x04, 0x08, 0x10, 0x20])],
+    &[
+        ('o', &[0x03, 0x05, 0x0a, 0x14, 0x28, 0x30]),
+        ('m', &[0x06, 0x09, 0x11, 0x18, 0x22, 0x24]),
+        ('p', &[0x0c, 0x12, 0x21]),
+    ],
+    &[
+        ('o', &[0x07, 0x0b, 0x15, 0x2a, 0x34, 0x38]),
+        (
+            'm',
+            &[
+                0x0d, 0x0e, 0x13, 0x16, 0x1a, 0x1c, 0x23, 0x25, 0x29, 0x2c, 0x31, 0x32,
+            ],
+        ),
+        ('p', &[0x19, 0x26]),
+    ],
+    &[
+        ('o', &[0x0f, 0x17, 0x2b, 0x35, 0x3a, 0x3c]),
+        ('m', &[0x1b, 0x1d, 0x27, 0x2e, 0x36, 0x39]),
+        ('p', &[0x1e, 0x2d, 0x33]),
+    ],
+    &[('o', &[0x1f, 0x2f, 0x37, 0x3b, 0x3d, 0x3e])],
+    &[('o', &[0x3f])],
+];
 
 rule_struct!(NtHex);
 
 impl NtHex {
-    parse_bs! {
-        '0' => {
-            'o' => [0x00],
-        },
-        '1' => {
-            'o' => [0x01, 0x02, 0x04, 0x08, 0x10, 0x20],
-        },
-        '2' => {
-            'o' => [0x03, 0x05, 0x0a, 0x14, 0x28, 0x30],
-            'm' => [0x06, 0x09, 0x11, 0x18, 0x22, 0x24],
-            'p' => [0x0c, 0x12, 0x21],
-        },
-        '3' => {
-            'o' => [0x07, 0x0b, 0x15, 0x2a, 0x34, 0x38],
-            'm' => [0x0d, 0x0e, 0x13, 0x16, 0x1a, 0x1c, 0x23, 0x25, 0x29, 0x2c, 0x31, 0x32],
-            'p' => [0x19, 0x26],
-        },
-        '4' => {
-            'o' => [0x0f, 0x17, 0x2b, 0x35, 0x3a, 0x3c],
-            'm' => [0x1b, 0x1d, 0x27, 0x2e, 0x36, 0x39],
-            'p' => [0x1e, 0x2d, 0x33],
-        },
-        '5' => {
-            'o' => [0x1f, 0x2f, 0x37, 0x3b, 0x3d, 0x3e],
-        },
-        '6' => {
-            'o' => [0x3f],
-        },
-    }
+    parse_bs!(hensel NTHEX_NEIGHBOR_MASKS);
     parse_rule!('H');
     parse_rule_map!(6);
+
+    /// Reduces a rule in the Moore neighborhood to the hexagonal neighborhood,
+    /// checking that the ignored neighbors, NE and SW, do not matter.
+    fn from_moore(MooreMap { b, s }: MooreMap) -> Result<Self, ParseRuleError> {
+        const IGNORED: u8 = 0x24;
+        let reduce = |bs: Vec<u8>| {
+            if bs.iter().any(|&i| {
+                [0x00, 0x04, 0x20, 0x24]
+                    .iter()
+                    .any(|&m| !bs.contains(&(i & !IGNORED | m)))
+            }) {
+                return Err(ParseRuleError::NotHexagonal);
+            }
+            Ok(bs
+                .into_iter()
+                .filter(|&i| i & IGNORED == 0)
+                .map(|i| (i & 0xc0) >> 2 | (i & 0x18) >> 1 | (i & 0x03))
+                .collect())
+        };
+        Ok(NtHex::from_bs(reduce(b)?, reduce(s)?))
+    }
+
+    /// A parser for MAP strings, in either the hexagonal neighborhood
+    /// or the Moore neighborhood used by Golly for hexagonal rules.
+    fn parse_map(input: &str, opts: &ParseOptions) -> Result<Self, ParseError> {
+        NtHex::parse_rule_map_with(input, opts).or_else(|e| {
+            let rule = MooreMap::parse_rule_map_with(input, opts).map_err(|_| e.clone())?;
+            // The data has the wrong length for the hexagonal neighborhood,
+            // so `e` points at its start.
+            NtHex::from_moore(rule).map_err(|kind| ParseError::at(kind, e.offset))
+        })
+    }
+
+    /// A parser for Generations MAP strings, in either the hexagonal neighborhood
+    /// or the Moore neighborhood used by Golly for hexagonal rules.
+    fn parse_gen_map(input: &str, opts: &ParseOptions) -> Result<Gen<Self>, ParseError> {
+        NtHex::parse_rule_gen_map_with(input, opts).or_else(|e| {
+            let Gen { rule, gen } =
+                MooreMap::parse_rule_gen_map_with(input, opts).map_err(|_| e.clone())?;
+            Ok(Gen {
+                rule: NtHex::from_moore(rule).map_err(|kind| ParseError::at(kind, e.offset))?,
+                gen,
+            })
+        })
+    }
+}
+
+// Golly writes MAP strings of hexagonal rules in the Moore neighborhood.
+rule_struct!(MooreMap);
+
+impl MooreMap {
+    parse_rule_map!(8);
 }
 
 impl_parser!(
@@ -94,17 +162,43 @@ pub trait ParseNtHex {
     where
         Self: Sized,
     {
-        let NtHex { b, s } = ParseHex::parse_rule(input)
-            .or_else(|_| NtHex::parse_rule(input))
-            .or_else(|e| {
-                NtHex::parse_rule_map(input).map_err(|e_map| {
-                    if e_map == ParseRuleError::NotMapRule {
+        Self::parse_rule_with(input, &ParseOptions::default())
+    }
+
+    /// The parser, with the given options.
+    fn parse_rule_with(input: &str, opts: &ParseOptions) -> Result<Self, ParseRuleError>
+    where
+        Self: Sized,
+    {
+        Self::parse_rule_at_with(input, opts).map_err(|e| e.kind)
+    }
+
+    /// The parser, returning the byte offset of the error.
+    fn parse_rule_at(input: &str) -> Result<Self, ParseError>
+    where
+        Self: Sized,
+    {
+        Self::parse_rule_at_with(input, &ParseOptions::default())
+    }
+
+    /// The parser, with the given options, returning the byte offset of the error.
+    fn parse_rule_at_with(input: &str, opts: &ParseOptions) -> Result<Self, ParseError>
+    where
+        Self: Sized,
+    {
+        let totalistic = parse_totalistic(input, opts, <NtHex as ParseHex>::parse_rule_at_with);
+        let NtHex { b, s } = match totalistic {
+            Some(rule) => rule,
+            None => NtHex::parse_rule_with(input, opts).or_else(|e| {
+                NtHex::parse_map(input, opts).map_err(|e_map| {
+                    if e_map.kind == ParseRuleError::NotMapRule {
                         e
                     } else {
                         e_map
                     }
                 })
-            })?;
+            })?,
+        };
         Ok(Self::from_bs(b, s))
     }
 }
@@ -154,36 +248,100 @@ pub trait ParseNtHexGen {
 
     /// The parser.
     fn parse_rule(input: &str) -> Result<Self, ParseRuleError>
+    where
+        Self: Sized,
+    {
+        Self::parse_rule_with(input, &ParseOptions::default())
+    }
+
+    /// The parser, with the given options.
+    fn parse_rule_with(input: &str, opts: &ParseOptions) -> Result<Self, ParseRuleError>
+    where
+        Self: Sized,
+    {
+        Self::parse_rule_at_with(input, opts).map_err(|e| e.kind)
+    }
+
+    /// The parser, returning the byte offset of the error.
+    fn parse_rule_at(input: &str) -> Result<Self, ParseError>
+    where
+        Self: Sized,
+    {
+        Self::parse_rule_at_with(input, &ParseOptions::default())
+    }
+
+    /// The parser, with the given options, returning the byte offset of the error.
+    fn parse_rule_at_with(input: &str, opts: &ParseOptions) -> Result<Self, ParseError>
     where
         Self: Sized,
     {
         let Gen {
             rule: NtHex { b, s },
             gen,
-        } = ParseHexGen::parse_rule(input)
-            .or_else(|_| NtHex::parse_rule_gen(input))
-            .or_else(|e| {
-                NtHex::parse_rule_gen_map(input).map_err(|e_map| {
-                    if e_map == ParseRuleError::NotMapRule {
-                        e
-                    } else {
-                        e_map
-                    }
-                })
-            })?;
+        } = parse_rule_gen(input, opts)?;
         Ok(Self::from_bsg(b, s, gen))
     }
+
+    /// A lenient parser that treats a number of states less than 2 as 2,
+    /// so that strings like `B2o/S2m34H/C1` or `g0b2os2m34h`, which some programs write
+    /// for rules with two states, are accepted.
+    fn parse_rule_lenient(input: &str) -> Result<Self, ParseRuleError>
+    where
+        Self: Sized,
+    {
+        let opts = ParseOptions {
+            lenient: true,
+            ..ParseOptions::default()
+        };
+        Self::parse_rule_with(input, &opts)
+    }
+}
+
+/// Tries each parser for Generations rules in turn.
+fn parse_rule_gen(input: &str, opts: &ParseOptions) -> Result<Gen<NtHex>, ParseError> {
+    if let Some(rule) =
+        parse_totalistic(input, opts, <Gen<NtHex> as ParseHexGen>::parse_rule_at_with)
+    {
+        return Ok(rule);
+    }
+    NtHex::parse_rule_gen_with(input, opts).or_else(|e| {
+        NtHex::parse_gen_map(input, opts).map_err(|e_map| {
+            if e_map.kind == ParseRuleError::NotMapRule {
+                e
+            } else {
+                e_map
+            }
+        })
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::MapDecodeError;
 
-    struct Rule;
+    #[derive(Debug, Eq, PartialEq)]
+    struct Rule {
+        b: Vec<u8>,
+        s: Vec<u8>,
+    }
 
     impl ParseNtHex for Rule {
-        fn from_bs(_b: Vec<u8>, _s: Vec<u8>) -> Self {
-            Rule
+        fn from_bs(b: Vec<u8>, s: Vec<u8>) -> Self {
+            Rule { b, s }
+        }
+    }
+
+    #[derive(Debug, Eq, PartialEq)]
+    struct GenRule {
+        b: Vec<u8>,
+        s: Vec<u8>,
+        gen: usize,
+    }
+
+    impl ParseNtHexGen for GenRule {
+        fn from_bsg(b: Vec<u8>, s: Vec<u8>, gen: usize) -> Self {
+            GenRule { b, s, gen }
         }
     }
 
@@ -199,6 +357,29 @@ mod tests {
 
     #[test]
     fn invalid_rules() {
+        let opts = ParseOptions::default();
+        assert_eq!(
+            Rule::parse_rule("B3-4/S23").err(),
+            Some(ParseRuleError::EmptyNegation(3))
+        );
+        assert_eq!(
+            Rule::parse_rule("B3-4/S23H").err(),
+            Some(ParseRuleError::EmptyNegation(3))
+        );
+        assert_eq!(
+            Rule::parse_rule("B2o/S27H").err(),
+            Some(ParseRuleError::CountTooLarge(7))
+        );
+        assert_eq!(
+            Rule::parse_rule("B2o/S2m7H").err(),
+            Some(ParseRuleError::CountTooLarge(7))
+        );
+        assert_eq!(
+            NtHex::parse_rule_with("B2o/S2m9H", &opts)
+                .map_err(|e| e.kind)
+                .err(),
+            Some(ParseRuleError::CountTooLarge(9))
+        );
         assert_eq!(
             Rule::parse_rule("B3/S23").err(),
             Some(ParseRuleError::Missing('H'))
@@ -208,16 +389,26 @@ mod tests {
             Some(ParseRuleError::Missing('S'))
         );
         assert_eq!(
-            Rule::parse_rule("b2o3-o4m12m3o4m5h").err(),
+            Rule::parse_rule("b2o3-o4m/12m3o4m5h").err(),
             Some(ParseRuleError::Missing('S'))
         );
         assert_eq!(
             Rule::parse_rule("B2o3p4-o5-p/S2-p3p45H").err(),
-            Some(ParseRuleError::Missing('S'))
+            Some(ParseRuleError::Unexpected('p'))
+        );
+        assert_eq!(
+            Rule::parse_rule("B2-/S2H").err(),
+            Some(ParseRuleError::EmptyNegation(2))
+        );
+        assert_eq!(
+            Rule::parse_rule("B2a/S2H").err(),
+            Some(ParseRuleError::Unexpected('a'))
         );
         assert_eq!(
             Rule::parse_rule("MAPFgFoF2gXgH5oF4B+gH4A6AH").err(),
-            Some(ParseRuleError::Base64Error)
+            Some(ParseRuleError::Base64Error(
+                MapDecodeError::InvalidLastSymbol(22, 72)
+            ))
         );
     }
 
@@ -236,9 +427,319 @@ mod tests {
 
     #[test]
     fn parse_map() -> Result<(), ParseRuleError> {
-        let rule1: NtHex = NtHex::parse_rule("B2/S34H")?;
-        let rule2: NtHex = NtHex::parse_rule_map("MAPFgFoF2gXgH5oF4B+gH4A6A")?;
+        let opts = ParseOptions::default();
+        let rule1: NtHex = NtHex::parse_rule_with("B2/S34H", &opts).map_err(|e| e.kind)?;
+        let rule2: NtHex =
+            NtHex::parse_rule_map_with("MAPFgFoF2gXgH5oF4B+gH4A6A", &opts).map_err(|e| e.kind)?;
         assert_eq!(rule1, rule2);
+        let rule3: NtHex =
+            NtHex::parse_rule_map_with("MAPFgFoF2gXgH5oF4B-gH4A6A", &opts).map_err(|e| e.kind)?;
+        assert_eq!(rule1, rule3);
+        let rule4: NtHex =
+            NtHex::parse_rule_map_with("MAPFgFoF2gXgH5oF4B+gH4A6A==", &opts).map_err(|e| e.kind)?;
+        assert_eq!(rule1, rule4);
+        assert_eq!(
+            NtHex::parse_rule_map_with("MAPFgFoF2gXgH5oF4B+gH4A6A===", &opts)
+                .map_err(|e| e.kind)
+                .err(),
+            Some(ParseRuleError::Base64Error(MapDecodeError::InvalidLength))
+        );
+        assert_eq!(
+            NtHex::parse_rule_map_with("MAPFgFoF2gXgH5oF4B-gH4A6+", &opts)
+                .map_err(|e| e.kind)
+                .err(),
+            Some(ParseRuleError::Base64Error(MapDecodeError::InvalidByte(
+                15, b'-'
+            )))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn hensel_tables() -> Result<(), ParseRuleError> {
+        let opts = ParseOptions::default();
+        // The neighbors in cyclic order, starting from the top left.
+        const CYCLE: [u8; 6] = [5, 4, 2, 0, 1, 3];
+        let transform = |i: u8, r: usize, reflect: bool| {
+            (0..6)
+                .filter(|&k| i & 1 << CYCLE[k] != 0)
+                .map(|k| {
+                    let k = if reflect { 6 - k } else { k };
+                    1 << CYCLE[(k + r) % 6]
+                })
+                .sum::<u8>()
+        };
+        // Each letter gives exactly one class of configurations under rotations and reflections,
+        // and the letters of each count cover all configurations with that many neighbors.
+        for n in 0..=6 {
+            let mut all = Vec::new();
+            for letter in "omp".chars() {
+                if let Ok(rule) = NtHex::parse_rule_with(&format!("B{}{}/SH", n, letter), &opts) {
+                    let mut orbit: Vec<u8> = (0..6)
+                        .flat_map(|r| [false, true].map(|reflect| transform(rule.b[0], r, reflect)))
+                        .collect();
+                    orbit.sort_unstable();
+                    orbit.dedup();
+                    assert_eq!(rule.b, orbit, "{}{}", n, letter);
+                    all.extend(rule.b);
+                }
+            }
+            all.sort_unstable();
+            let mut masks: Vec<u8> = NTHEX_NEIGHBOR_MASKS[n as usize]
+                .iter()
+                .flat_map(|&(_, masks)| masks.iter().copied())
+                .collect();
+            masks.sort_unstable();
+            assert_eq!(all, masks);
+            assert_eq!(
+                all,
+                NtHex::parse_rule_with(&format!("B{}/SH", n), &opts)
+                    .map_err(|e| e.kind)?
+                    .b
+            );
+            assert_eq!(
+                all,
+                (0..=0x3fu8)
+                    .filter(|b| b.count_ones() == n)
+                    .collect::<Vec<_>>()
+            );
+        }
+
+        let rule = NtHex::parse_rule_with("B2o3-o4m/S12m3o4m5H", &opts).map_err(|e| e.kind)?;
+        assert_eq!(
+            rule.b,
+            vec![
+                0x03, 0x05, 0x0a, 0x0d, 0x0e, 0x13, 0x14, 0x16, 0x19, 0x1a, 0x1b, 0x1c, 0x1d, 0x23,
+                0x25, 0x26, 0x27, 0x28, 0x29, 0x2c, 0x2e, 0x30, 0x31, 0x32, 0x36, 0x39
+            ]
+        );
+        assert!(rule.s.contains(&0x01) && rule.s.contains(&0x09) && !rule.s.contains(&0x03));
+        Ok(())
+    }
+
+    #[test]
+    fn parse_golly_map() -> Result<(), ParseRuleError> {
+        let opts = ParseOptions::default();
+        let rule: NtHex = NtHex::parse_rule_with("B2/S34H", &opts).map_err(|e| e.kind)?;
+        assert_eq!(
+            NtHex::parse_map(
+                "MAPEWYAEWaIEXcRZgARZogRd2aIEXeIAHfuZogRd4gAd+5miBF3iAB37maIEXeIAHfuiAB37gAA7oiIAHfuAADuiA",
+                &opts,
+            )
+            .map_err(|e| e.kind)?,
+            rule
+        );
+        assert_eq!(
+            NtHex::parse_map("MAPFgFoF2gXgH5oF4B+gH4A6A", &opts).map_err(|e| e.kind)?,
+            rule
+        );
+        let gen: Gen<NtHex> = NtHex::parse_gen_map(
+            "MAPEWYAEWaIEXcRZgARZogRd2aIEXeIAHfuZogRd4gAd+5miBF3iAB37maIEXeIAHfuiAB37gAA7oiIAHfuAADuiA/4",
+            &opts,
+        )
+        .map_err(|e| e.kind)?;
+        assert_eq!(gen.gen, 4);
+        assert_eq!(gen.rule, rule);
+        assert_eq!(
+            NtHex::parse_map(
+                "MAPEWYAEWaIEXeRZgARZogRd2aIEXeIAHfuZogRd4gAd+5miBF3iAB37maIEXeIAHfuiAB37gAA7oiIAHfuAADuiA",
+                &opts,
+            )
+            .err(),
+            Some(ParseError {
+                kind: ParseRuleError::NotHexagonal,
+                offset: 3,
+            })
+        );
+        assert_eq!(
+            NtHex::parse_map("MAPFgFoF2gXgH5oF4B+gH4A6AAA", &opts).err(),
+            Some(ParseError {
+                kind: ParseRuleError::InvalidLength {
+                    expected: 0x80,
+                    found: 0x90
+                },
+                offset: 3,
+            })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn empty_rule() -> Result<(), ParseRuleError> {
+        for input in ["B/SH", "/H", "bsh", "MAPAAAAAAAAAAAAAAAAAAAAAA"] {
+            assert_eq!(
+                Rule::parse_rule(input)?,
+                Rule {
+                    b: vec![],
+                    s: vec![]
+                },
+                "{:?}",
+                input
+            );
+        }
+        for input in [
+            "B/S/C3H",
+            "//3H",
+            "g3bsh",
+            "MAPAAAAAAAAAAAAAAAAAAAAAA/3",
+            "g3MAPAAAAAAAAAAAAAAAAAAAAAA",
+        ] {
+            assert_eq!(
+                GenRule::parse_rule(input)?,
+                GenRule {
+                    b: vec![],
+                    s: vec![],
+                    gen: 3
+                },
+                "{:?}",
+                input
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn surrounding_whitespace() -> Result<(), ParseRuleError> {
+        for input in [
+            " B2o3-o4m/S12m3o4m5H",
+            "B2o3-o4m/S12m3o4m5H\n",
+            "\tMAPFgFoF2gXgH5oF4B+gH4A6A ",
+        ] {
+            assert_eq!(
+                Rule::parse_rule(input)?,
+                Rule::parse_rule(input.trim())?,
+                "{:?}",
+                input
+            );
+        }
+        for input in [" B2o/S2m34H/C3\n", "\tg3MAPFgFoF2gXgH5oF4B+gH4A6A "] {
+            assert_eq!(
+                GenRule::parse_rule(input)?,
+                GenRule::parse_rule(input.trim())?,
+                "{:?}",
+                input
+            );
+        }
+        assert_eq!(
+            Rule::parse_rule("MAPFgFoF2gXg H5oF4B+gH4A6A")?,
+            Rule::parse_rule("MAPFgFoF2gXgH5oF4B+gH4A6A")?
+        );
+        assert_eq!(
+            Rule::parse_rule("B2o3 -o4m/S12m3o4m5H").err(),
+            Some(ParseRuleError::Missing('S'))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn empty_input() {
+        for input in ["", " ", "\t\r\n"] {
+            assert_eq!(
+                Rule::parse_rule(input).err(),
+                Some(ParseRuleError::EmptyInput)
+            );
+            assert_eq!(
+                GenRule::parse_rule(input).err(),
+                Some(ParseRuleError::EmptyInput)
+            );
+            assert_eq!(
+                GenRule::parse_rule_lenient(input).err(),
+                Some(ParseRuleError::EmptyInput)
+            );
+        }
+    }
+
+    #[test]
+    fn trailing_slash() {
+        assert_eq!(
+            Rule::parse_rule("B3/S23H/").err(),
+            Some(ParseRuleError::extra_junk('/', 7))
+        );
+        assert_eq!(
+            Rule::parse_rule("B2o/S2mH/").err(),
+            Some(ParseRuleError::extra_junk('/', 8))
+        );
+        assert_eq!(
+            GenRule::parse_rule("B3/S23H/").err(),
+            Some(ParseRuleError::MissingNumber)
+        );
+        assert_eq!(
+            GenRule::parse_rule("B2o/S2mH/").err(),
+            Some(ParseRuleError::MissingNumber)
+        );
+        assert_eq!(
+            GenRule::parse_rule("g3b2os2mh/").err(),
+            Some(ParseRuleError::extra_junk('/', 9))
+        );
+        assert_eq!(
+            GenRule::parse_rule("MAPFgFoF2gXgH5oF4B+gH4A6A/").err(),
+            Some(ParseRuleError::MissingNumber)
+        );
+    }
+
+    #[test]
+    fn case_sensitive() -> Result<(), ParseRuleError> {
+        let opts = ParseOptions {
+            case_sensitive: true,
+            ..ParseOptions::default()
+        };
+        Rule::parse_rule_with("B2o/S2m34H", &opts)?;
+        GenRule::parse_rule_with("B2o/S2m34H/C3", &opts)?;
+        assert_eq!(
+            Rule::parse_rule_with("B2o/S2m34h", &opts).err(),
+            Some(ParseRuleError::Unexpected('h'))
+        );
+        assert_eq!(
+            GenRule::parse_rule_with("g3B2o/S2m34H", &opts).err(),
+            Some(ParseRuleError::Unexpected('g'))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn sb_notation() -> Result<(), ParseRuleError> {
+        let opts = ParseOptions {
+            allow_sb_notation: false,
+            ..ParseOptions::default()
+        };
+        Rule::parse_rule_with("B2o/S2mH", &opts)?;
+        GenRule::parse_rule_with("g3b2os2mh", &opts)?;
+        assert_eq!(
+            Rule::parse_rule_with("2m/2oH", &opts).err(),
+            Some(ParseRuleError::Missing('B'))
+        );
+        assert_eq!(
+            GenRule::parse_rule_with("2m/2o/3H", &opts).err(),
+            Some(ParseRuleError::Missing('B'))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn suffix_order_gen() -> Result<(), ParseRuleError> {
+        let rule = GenRule::parse_rule("B2o/S2m34/C3H")?;
+        for input in [
+            "B2o/S2m34/3H",
+            "B2o/S2m34C3H",
+            "B2o/S2m34H/C3",
+            "B2o/S2m34H/3",
+            "B2o/S2m34HC3",
+            "B2o/S2m34H3",
+            "b2os2m34hg3",
+            "2m34/2o/3H",
+            "2m34/2o/C3H",
+            "2m34/2oH/3",
+            "2m34/2oH/G3",
+            "2m34/2oH3",
+            "2m34/2oh3",
+            "g3b2os2m34h",
+        ] {
+            assert_eq!(GenRule::parse_rule(input)?, rule, "{}", input);
+        }
+        assert_eq!(
+            GenRule::parse_rule("B2o/S2m34H/3H").err(),
+            Some(ParseRuleError::extra_junk('H', 12))
+        );
         Ok(())
     }
 }
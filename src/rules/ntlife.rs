@@ -4,84 +4,99 @@ use super::{
     life::{ParseLife, ParseLifeGen},
     nthex::{ParseNtHex, ParseNtHexGen},
     ntneumann::{ParseNtNeumann, ParseNtNeumannGen},
-    Gen,
+    parse_totalistic, Gen,
 };
-use crate::ParseRuleError;
+use crate::{ParseError, ParseOptions, ParseRuleError};
+
+/// The neighborhoods of each Hensel letter in isotropic non-totalistic life-like rules.
+///
+/// The table is indexed by the number of neighbors. Each entry lists the letters for that count,
+/// together with the neighborhoods that each letter stands for, represented as in [`ParseNtLife`].
+/// A count without letters, e.g., the `3` in `B3/S23`, stands for all of them.
+///
+/// # Examples
+///
+/// ```
+/// use ca_rules::NTLIFE_NEIGHBOR_MASKS;
+///
+/// let (_, masks) = NTLIFE_NEIGHBOR_MASKS[3]
+///     .iter()
+///     .find(|&&(letter, _)| letter == 'a')
+///     .unwrap();
+/// assert_eq!(masks, &[0x0b, 0x16, 0x68, 0xd0]);
+/// ```
+pub const NTLIFE_NEIGHBOR_MASKS: [&[(char, &[u8])]; 9] = [
+    &[('c', &[0x00])],
+    &[
+        ('c', &[0x01, 0x04, 0x20, 0x80]),
+        ('e', &[0x02, 0x08, 0x10, 0x40]),
+    ],
+    &[
+        ('c', &[0x05, 0x21, 0x84, 0xa0]),
+        ('e', &[0x0a, 0x12, 0x48, 0x50]),
+        ('k', &[0x0c, 0x11, 0x22, 0x30, 0x41, 0x44, 0x82, 0x88]),
+        ('a', &[0x03, 0x06, 0x09, 0x14, 0x28, 0x60, 0x90, 0xc0]),
+        ('i', &[0x18, 0x42]),
+        ('n', &[0x24, 0x81]),
+    ],
+    &[
+        ('c', &[0x25, 0x85, 0xa1, 0xa4]),
+        ('e', &[0x1a, 0x4a, 0x52, 0x58]),
+        ('k', &[0x32, 0x4c, 0x51, 0x8a]),
+        ('a', &[0x0b, 0x16, 0x68, 0xd0]),
+        ('i', &[0x07, 0x29, 0x94, 0xe0]),
+        ('n', &[0x0d, 0x15, 0x23, 0x61, 0x86, 0xa8, 0xb0, 0xc4]),
+        ('y', &[0x31, 0x45, 0x8c, 0xa2]),
+        ('q', &[0x26, 0x2c, 0x34, 0x64, 0x83, 0x89, 0x91, 0xc1]),
+        ('j', &[0x0e, 0x13, 0x2a, 0x49, 0x54, 0x70, 0x92, 0xc8]),
+        ('r', &[0x19, 0x1c, 0x38, 0x43, 0x46, 0x62, 0x98, 0xc2]),
+    ],
+    &[
+        ('c', &[0xa5]),
+        ('e', &[0x5a]),
+        ('k', &[0x33, 0x4d, 0x55, 0x71, 0x8e, 0xaa, 0xb2, 0xcc]),
+        ('a', &[0x0f, 0x17, 0x2b, 0x69, 0x96, 0xd4, 0xe8, 0xf0]),
+        ('i', &[0x1d, 0x63, 0xb8, 0xc6]),
+        ('n', &[0x27, 0x2d, 0x87, 0x95, 0xa9, 0xb4, 0xe1, 0xe4]),
+        ('y', &[0x35, 0x65, 0x8d, 0xa3, 0xa6, 0xac, 0xb1, 0xc5]),
+        ('q', &[0x36, 0x6c, 0x8b, 0xd1]),
+        ('j', &[0x3a, 0x4e, 0x53, 0x59, 0x5c, 0x72, 0x9a, 0xca]),
+        ('r', &[0x1b, 0x1e, 0x4b, 0x56, 0x6a, 0x78, 0xd2, 0xd8]),
+        ('t', &[0x39, 0x47, 0x9c, 0xe2]),
+        ('w', &[0x2e, 0x74, 0x93, 0xc9]),
+        ('z', &[0x3c, 0x66, 0x99, 0xc3]),
+    ],
+    &[
+        ('c', &[0x5b, 0x5e, 0x7a, 0xda]),
+        ('e', &[0xa7, 0xad, 0xb5, 0xe5]),
+        ('k', &[0x75, 0xae, 0xb3, 0xcd]),
+        ('a', &[0x2f, 0x97, 0xe9, 0xf4]),
+        ('i', &[0x1f, 0x6b, 0xd6, 0xf8]),
+        ('n', &[0x3b, 0x4f, 0x57, 0x79, 0x9e, 0xdc, 0xea, 0xf2]),
+        ('y', &[0x5d, 0x73, 0xba, 0xce]),
+        ('q', &[0x3e, 0x6e, 0x76, 0x7c, 0x9b, 0xcb, 0xd3, 0xd9]),
+        ('j', &[0x37, 0x6d, 0x8f, 0xab, 0xb6, 0xd5, 0xec, 0xf1]),
+        ('r', &[0x3d, 0x67, 0x9d, 0xb9, 0xbc, 0xc7, 0xe3, 0xe6]),
+    ],
+    &[
+        ('c', &[0x5f, 0x7b, 0xde, 0xfa]),
+        ('e', &[0xaf, 0xb7, 0xed, 0xf5]),
+        ('k', &[0x77, 0x7d, 0xbb, 0xbe, 0xcf, 0xdd, 0xee, 0xf3]),
+        ('a', &[0x3f, 0x6f, 0x9f, 0xd7, 0xeb, 0xf6, 0xf9, 0xfc]),
+        ('i', &[0xbd, 0xe7]),
+        ('n', &[0x7e, 0xdb]),
+    ],
+    &[
+        ('c', &[0x7f, 0xdf, 0xfb, 0xfe]),
+        ('e', &[0xbf, 0xef, 0xf7, 0xfd]),
+    ],
+    &[('c', &[0xff])],
+];
 
 rule_struct!(NtLife);
 
 impl NtLife {
-    parse_bs! {
-        '0' => {
-            'c' => [0x00],
-        },
-        '1' => {
-            'c' => [0x01, 0x04, 0x20, 0x80],
-            'e' => [0x02, 0x08, 0x10, 0x40],
-        },
-        '2' => {
-            'c' => [0x05, 0x21, 0x84, 0xa0],
-            'e' => [0x0a, 0x12, 0x48, 0x50],
-            'k' => [0x0c, 0x11, 0x22, 0x30, 0x41, 0x44, 0x82, 0x88],
-            'a' => [0x03, 0x06, 0x09, 0x14, 0x28, 0x60, 0x90, 0xc0],
-            'i' => [0x18, 0x42],
-            'n' => [0x24, 0x81],
-        },
-        '3' => {
-            'c' => [0x25, 0x85, 0xa1, 0xa4],
-            'e' => [0x1a, 0x4a, 0x52, 0x58],
-            'k' => [0x32, 0x4c, 0x51, 0x8a],
-            'a' => [0x0b, 0x16, 0x68, 0xd0],
-            'i' => [0x07, 0x29, 0x94, 0xe0],
-            'n' => [0x0d, 0x15, 0x23, 0x61, 0x86, 0xa8, 0xb0, 0xc4],
-            'y' => [0x31, 0x45, 0x8c, 0xa2],
-            'q' => [0x26, 0x2c, 0x34, 0x64, 0x83, 0x89, 0x91, 0xc1],
-            'j' => [0x0e, 0x13, 0x2a, 0x49, 0x54, 0x70, 0x92, 0xc8],
-            'r' => [0x19, 0x1c, 0x38, 0x43, 0x46, 0x62, 0x98, 0xc2],
-        },
-        '4' => {
-            'c' => [0xa5],
-            'e' => [0x5a],
-            'k' => [0x33, 0x4d, 0x55, 0x71, 0x8e, 0xaa, 0xb2, 0xcc],
-            'a' => [0x0f, 0x17, 0x2b, 0x69, 0x96, 0xd4, 0xe8, 0xf0],
-            'i' => [0x1d, 0x63, 0xb8, 0xc6],
-            'n' => [0x27, 0x2d, 0x87, 0x95, 0xa9, 0xb4, 0xe1, 0xe4],
-            'y' => [0x35, 0x65, 0x8d, 0xa3, 0xa6, 0xac, 0xb1, 0xc5],
-            'q' => [0x36, 0x6c, 0x8b, 0xd1],
-            'j' => [0x3a, 0x4e, 0x53, 0x59, 0x5c, 0x72, 0x9a, 0xca],
-            'r' => [0x1b, 0x1e, 0x4b, 0x56, 0x6a, 0x78, 0xd2, 0xd8],
-            't' => [0x39, 0x47, 0x9c, 0xe2],
-            'w' => [0x2e, 0x74, 0x93, 0xc9],
-            'z' => [0x3c, 0x66, 0x99, 0xc3],
-        },
-        '5' => {
-            'c' => [0x5b, 0x5e, 0x7a, 0xda],
-            'e' => [0xa7, 0xad, 0xb5, 0xe5],
-            'k' => [0x75, 0xae, 0xb3, 0xcd],
-            'a' => [0x2f, 0x97, 0xe9, 0xf4],
-            'i' => [0x1f, 0x6b, 0xd6, 0xf8],
-            'n' => [0x3b, 0x4f, 0x57, 0x79, 0x9e, 0xdc, 0xea, 0xf2],
-            'y' => [0x5d, 0x73, 0xba, 0xce],
-            'q' => [0x3e, 0x6e, 0x76, 0x7c, 0x9b, 0xcb, 0xd3, 0xd9],
-            'j' => [0x37, 0x6d, 0x8f, 0xab, 0xb6, 0xd5, 0xec, 0xf1],
-            'r' => [0x3d, 0x67, 0x9d, 0xb9, 0xbc, 0xc7, 0xe3, 0xe6],
-        },
-        '6' => {
-            'c' => [0x5f, 0x7b, 0xde, 0xfa],
-            'e' => [0xaf, 0xb7, 0xed, 0xf5],
-            'k' => [0x77, 0x7d, 0xbb, 0xbe, 0xcf, 0xdd, 0xee, 0xf3],
-            'a' => [0x3f, 0x6f, 0x9f, 0xd7, 0xeb, 0xf6, 0xf9, 0xfc],
-            'i' => [0xbd, 0xe7],
-            'n' => [0x7e, 0xdb],
-        },
-        '7' => {
-            'c' => [0x7f, 0xdf, 0xfb, 0xfe],
-            'e' => [0xbf, 0xef, 0xf7, 0xfd],
-        },
-        '8' => {
-            'c' => [0xff],
-        },
-    }
+    parse_bs!(hensel NTLIFE_NEIGHBOR_MASKS);
     parse_rule!();
     parse_rule_map!(8);
 }
@@ -150,19 +165,40 @@ pub trait ParseNtLife {
     where
         Self: Sized,
     {
-        let NtLife { b, s } = ParseLife::parse_rule(input)
-            .or_else(|_| NtLife::parse_rule(input))
-            .or_else(|e| ParseNtHex::parse_rule(input).map_err(|_| e))
-            .or_else(|e| ParseNtNeumann::parse_rule(input).map_err(|_| e))
-            .or_else(|e| {
-                NtLife::parse_rule_map(input).map_err(|e_map| {
-                    if e_map == ParseRuleError::NotMapRule {
-                        e
-                    } else {
-                        e_map
-                    }
-                })
-            })?;
+        Self::parse_rule_with(input, &ParseOptions::default())
+    }
+
+    /// The parser, with the given options.
+    fn parse_rule_with(input: &str, opts: &ParseOptions) -> Result<Self, ParseRuleError>
+    where
+        Self: Sized,
+    {
+        Self::parse_rule_at_with(input, opts).map_err(|e| e.kind)
+    }
+
+    /// The parser, returning the byte offset of the error.
+    fn parse_rule_at(input: &str) -> Result<Self, ParseError>
+    where
+        Self: Sized,
+    {
+        Self::parse_rule_at_with(input, &ParseOptions::default())
+    }
+
+    /// The parser, with the given options, returning the byte offset of the error.
+    fn parse_rule_at_with(input: &str, opts: &ParseOptions) -> Result<Self, ParseError>
+    where
+        Self: Sized,
+    {
+        let totalistic = parse_totalistic(input, opts, <NtLife as ParseLife>::parse_rule_at_with);
+        let NtLife { b, s } = match totalistic {
+            Some(rule) => rule,
+            None => NtLife::parse_rule_with(input, opts)
+                .or_else(|e| ParseNtHex::parse_rule_at_with(input, opts).map_err(|_| e))
+                .or_else(|e| ParseNtNeumann::parse_rule_at_with(input, opts).map_err(|_| e))
+                .or_else(|e| {
+                    NtLife::parse_rule_map_with(input, opts).map_err(|e_map| map_error(e, e_map))
+                })?,
+        };
         Ok(Self::from_bs(b, s))
     }
 }
@@ -212,46 +248,132 @@ pub trait ParseNtLifeGen {
 
     /// The parser.
     fn parse_rule(input: &str) -> Result<Self, ParseRuleError>
+    where
+        Self: Sized,
+    {
+        Self::parse_rule_with(input, &ParseOptions::default())
+    }
+
+    /// The parser, with the given options.
+    fn parse_rule_with(input: &str, opts: &ParseOptions) -> Result<Self, ParseRuleError>
+    where
+        Self: Sized,
+    {
+        Self::parse_rule_at_with(input, opts).map_err(|e| e.kind)
+    }
+
+    /// The parser, returning the byte offset of the error.
+    fn parse_rule_at(input: &str) -> Result<Self, ParseError>
+    where
+        Self: Sized,
+    {
+        Self::parse_rule_at_with(input, &ParseOptions::default())
+    }
+
+    /// The parser, with the given options, returning the byte offset of the error.
+    fn parse_rule_at_with(input: &str, opts: &ParseOptions) -> Result<Self, ParseError>
     where
         Self: Sized,
     {
         let Gen {
             rule: NtLife { b, s },
             gen,
-        } = ParseLifeGen::parse_rule(input)
-            .or_else(|_| NtLife::parse_rule_gen(input))
-            .or_else(|e| ParseNtHexGen::parse_rule(input).map_err(|_| e))
-            .or_else(|e| ParseNtNeumannGen::parse_rule(input).map_err(|_| e))
-            .or_else(|e| {
-                NtLife::parse_rule_gen_map(input).map_err(|e_map| {
-                    if e_map == ParseRuleError::NotMapRule {
-                        e
-                    } else {
-                        e_map
-                    }
-                })
-            })?;
+        } = parse_rule_gen(input, opts)?;
         Ok(Self::from_bsg(b, s, gen))
     }
+
+    /// A lenient parser that treats a number of states less than 2 as 2,
+    /// so that strings like `B3/S2-i34q/C1` or `g0b3s2-i34q`, which some programs write
+    /// for rules with two states, are accepted.
+    fn parse_rule_lenient(input: &str) -> Result<Self, ParseRuleError>
+    where
+        Self: Sized,
+    {
+        let opts = ParseOptions {
+            lenient: true,
+            ..ParseOptions::default()
+        };
+        Self::parse_rule_with(input, &opts)
+    }
+}
+
+/// Tries each parser for Generations rules in turn.
+fn parse_rule_gen(input: &str, opts: &ParseOptions) -> Result<Gen<NtLife>, ParseError> {
+    if let Some(rule) = parse_totalistic(
+        input,
+        opts,
+        <Gen<NtLife> as ParseLifeGen>::parse_rule_at_with,
+    ) {
+        return Ok(rule);
+    }
+    NtLife::parse_rule_gen_with(input, opts)
+        .or_else(|e| ParseNtHexGen::parse_rule_at_with(input, opts).map_err(|_| e))
+        .or_else(|e| ParseNtNeumannGen::parse_rule_at_with(input, opts).map_err(|_| e))
+        .or_else(|e| {
+            NtLife::parse_rule_gen_map_with(input, opts).map_err(|e_map| map_error(e, e_map))
+        })
+}
+
+/// Chooses the error to report when the MAP parser is the last one to fail.
+///
+/// MAP strings of all three neighborhoods are accepted, so a wrong length
+/// is reported against all of them.
+fn map_error(e: ParseError, e_map: ParseError) -> ParseError {
+    match e_map.kind {
+        ParseRuleError::NotMapRule => e,
+        ParseRuleError::InvalidLength { found, .. } => ParseError {
+            kind: ParseRuleError::UnknownMapLength { found },
+            ..e_map
+        },
+        _ => e_map,
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::MapDecodeError;
 
-    struct Rule;
+    #[derive(Debug, Eq, PartialEq)]
+    struct Rule {
+        b: Vec<u8>,
+        s: Vec<u8>,
+    }
 
     impl ParseNtLife for Rule {
-        fn from_bs(_b: Vec<u8>, _s: Vec<u8>) -> Self {
-            Rule
+        fn from_bs(b: Vec<u8>, s: Vec<u8>) -> Self {
+            Rule { b, s }
+        }
+    }
+
+    #[derive(Debug, Eq, PartialEq)]
+    struct GenRule {
+        b: Vec<u8>,
+        s: Vec<u8>,
+        gen: usize,
+    }
+
+    impl ParseNtLifeGen for GenRule {
+        fn from_bsg(b: Vec<u8>, s: Vec<u8>, gen: usize) -> Self {
+            GenRule { b, s, gen }
         }
     }
 
     #[test]
     fn valid_rules() -> Result<(), ParseRuleError> {
+        let opts = ParseOptions::default();
         Rule::parse_rule("B3/S23")?;
         Rule::parse_rule("B3/S23V")?;
         Rule::parse_rule("B2e3-anq/S12-a3")?;
+        Rule::parse_rule("B3-c/S23")?;
+        assert_eq!(
+            NtLife::parse_rule_with("B2-a3i", &opts).map_err(|e| e.kind)?,
+            NtLife::parse_rule_with("B2-a3i/S", &opts).map_err(|e| e.kind)?
+        );
+        let rule: NtLife = ParseNtHex::parse_rule("B2o3mH")?;
+        assert_eq!(rule, ParseNtHex::parse_rule("B2o3m/SH")?);
+        let rule: NtLife = ParseNtNeumann::parse_rule("B2V")?;
+        assert_eq!(rule, ParseNtNeumann::parse_rule("B2/SV")?);
         Rule::parse_rule("B35y/S1e2-ci3-a5i")?;
         Rule::parse_rule("B2o3p4-o5/S2-p3p45H")?;
         Rule::parse_rule("MAPFgFoF2gXgH5oF4B+gH4A6A")?;
@@ -262,32 +384,261 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn empty_input() {
+        let opts = ParseOptions::default();
+        for input in ["", " ", "\t\r\n"] {
+            assert_eq!(
+                Rule::parse_rule(input).err(),
+                Some(ParseRuleError::EmptyInput)
+            );
+            assert_eq!(
+                GenRule::parse_rule(input).err(),
+                Some(ParseRuleError::EmptyInput)
+            );
+            assert_eq!(
+                GenRule::parse_rule_lenient(input).err(),
+                Some(ParseRuleError::EmptyInput)
+            );
+            assert_eq!(
+                NtLife::parse_rule_map_with(input, &opts)
+                    .map_err(|e| e.kind)
+                    .err(),
+                Some(ParseRuleError::EmptyInput)
+            );
+            assert_eq!(
+                NtLife::parse_rule_gen_map_with(input, &opts)
+                    .map_err(|e| e.kind)
+                    .err(),
+                Some(ParseRuleError::EmptyInput)
+            );
+        }
+    }
+
     #[test]
     fn invalid_rules() {
+        let opts = ParseOptions::default();
+        assert_eq!(
+            Rule::parse_rule("B3-4/S23").err(),
+            Some(ParseRuleError::EmptyNegation(3))
+        );
+        assert_eq!(
+            Rule::parse_rule("B2-5/S").err(),
+            Some(ParseRuleError::EmptyNegation(2))
+        );
+        assert_eq!(
+            Rule::parse_rule("B3,4/S23").err(),
+            Some(ParseRuleError::Missing('S'))
+        );
+        assert_eq!(
+            Rule::parse_rule("B3/S8-").err(),
+            Some(ParseRuleError::EmptyNegation(8))
+        );
+        assert_eq!(
+            Rule::parse_rule("B2c/S29").err(),
+            Some(ParseRuleError::CountTooLarge(9))
+        );
+        assert_eq!(
+            Rule::parse_rule("B2c/S29").err().unwrap().to_string(),
+            "Neighbor count 9 too large for the neighborhood"
+        );
+        assert_eq!(
+            NtLife::parse_rule_with("B2c9/S2", &opts)
+                .map_err(|e| e.kind)
+                .err(),
+            Some(ParseRuleError::CountTooLarge(9))
+        );
         assert_eq!(
             Rule::parse_rule("12-a3/B2e3-anq").err(),
-            Some(ParseRuleError::ExtraJunk)
+            Some(ParseRuleError::extra_junk('B', 6))
         );
         assert_eq!(
             Rule::parse_rule("B35y/1e2-ci3-a5i").err(),
             Some(ParseRuleError::Missing('S'))
         );
         assert_eq!(
-            Rule::parse_rule("B2i34cj6a7c82-i3-a4ceit6in").err(),
+            Rule::parse_rule("B2i34cj6a7c8/2-i3-a4ceit6in").err(),
             Some(ParseRuleError::Missing('S'))
         );
         assert_eq!(
             Rule::parse_rule("B2c3aenq4aijryz5cikqrz6ac8/S1e2cik3ejqry4anrwz5a6k").err(),
-            Some(ParseRuleError::Missing('S'))
+            Some(ParseRuleError::Unexpected('z'))
+        );
+        assert_eq!(
+            Rule::parse_rule("B3x/S23").err(),
+            Some(ParseRuleError::Unexpected('x'))
+        );
+        assert_eq!(
+            Rule::parse_rule("B2q/S12").err(),
+            Some(ParseRuleError::Unexpected('q'))
+        );
+        assert_eq!(
+            Rule::parse_rule("B2a3-kz/S23").err(),
+            Some(ParseRuleError::Unexpected('z'))
+        );
+        assert_eq!(
+            Rule::parse_rule("B3-/S23").err(),
+            Some(ParseRuleError::EmptyNegation(3))
+        );
+        assert_eq!(
+            Rule::parse_rule("B3-4c/S23").err(),
+            Some(ParseRuleError::EmptyNegation(3))
+        );
+        assert_eq!(
+            Rule::parse_rule("B3/S23-").err(),
+            Some(ParseRuleError::EmptyNegation(3))
+        );
+        assert_eq!(
+            Rule::parse_rule("B3/S2k3aX").err(),
+            Some(ParseRuleError::Unexpected('X'))
         );
         assert_eq!(
             Rule::parse_rule("MAPARYXfhZofugWaH7oaIDogBZofuhogOiAaIDogIAAgAAWaH7oaIDogGiA6ICAAIAAaIDogIAAgACAAIA").err(),
-            Some(ParseRuleError::InvalidLength)
+            Some(ParseRuleError::UnknownMapLength { found: 472 })
+        );
+        assert_eq!(
+            Rule::parse_rule("MAPHmlphgA").err().unwrap().to_string(),
+            "Invalid length for MAP rule: found 40 bits, \
+             expected 512 (Moore), 128 (hexagonal) or 32 (von Neumann)"
+        );
+        assert_eq!(
+            GenRule::parse_rule("MAPHmlphgA").err(),
+            Some(ParseRuleError::UnknownMapLength { found: 40 })
         );
         assert_eq!(
             Rule::parse_rule("MAPARYXfhZofugWaH7oaIDogBZofuhogOiAaIDogIAAgAAWaH7oaIDogGiA6ICAAIAAaIDogIAAgACAAIAAAAAAAX").err(),
-            Some(ParseRuleError::Base64Error)
+            Some(ParseRuleError::Base64Error(MapDecodeError::InvalidLastSymbol(85, 88)))
         );
+        assert_eq!(Rule::parse_rule("").err(), Some(ParseRuleError::EmptyInput));
+    }
+
+    #[test]
+    fn lenient_rules_gen() -> Result<(), ParseRuleError> {
+        let opts = ParseOptions::default();
+        for input in [
+            "B3/S23/C1",
+            "g1b2-a3s23",
+            "B2o/S2m/C0H",
+            "B2/S013/G1V",
+            "MAPHmlphg/1",
+            "MAPARYBFxZpF38WaRd/aZZ//hZpF39pln/+aZZ//pZp/ukWaRd/aZZ//mmWf/6Waf7paZZ//pZp/umWaf7paZbplg/0",
+        ] {
+            assert_eq!(GenRule::parse_rule_lenient(input)?.gen, 2);
+            assert!(GenRule::parse_rule(input).is_err(), "{:?}", input);
+        }
+        assert_eq!(
+            GenRule::parse_rule("g1b2-a3s23").err(),
+            Some(ParseRuleError::GenLessThan2)
+        );
+        assert_eq!(
+            NtLife::parse_rule_gen_map_with("MAPARYBFxZpF38WaRd/aZZ//hZpF39pln/+aZZ//pZp/ukWaRd/aZZ//mmWf/6Waf7paZZ//pZp/umWaf7paZbplg/1", &opts).map_err(|e| e.kind).err(),
+            Some(ParseRuleError::GenLessThan2)
+        );
+        assert_eq!(GenRule::parse_rule_lenient("g4b2-a3s23")?.gen, 4);
+        assert_eq!(
+            GenRule::parse_rule_lenient("B2-a/S23/Cx").err(),
+            Some(ParseRuleError::MissingNumber)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn sorted_data() -> Result<(), ParseRuleError> {
+        let opts = ParseOptions::default();
+        let rule = NtLife::parse_rule_with("B2ck/S23", &opts).map_err(|e| e.kind)?;
+        assert_eq!(
+            NtLife::parse_rule_with("B2kc/S32", &opts).map_err(|e| e.kind)?,
+            rule
+        );
+        assert_eq!(
+            NtLife::parse_rule_with("B2ckc/S233", &opts).map_err(|e| e.kind)?,
+            rule
+        );
+        assert_eq!(
+            NtLife::parse_rule_with("B2-aein/S23", &opts).map_err(|e| e.kind)?,
+            rule
+        );
+        assert_eq!(
+            NtLife::parse_rule_with("B2c2k/S232", &opts).map_err(|e| e.kind)?,
+            rule
+        );
+        assert!(rule.b.windows(2).all(|w| w[0] < w[1]));
+        let gen = NtLife::parse_rule_gen_with("B2kcc/S32/C3", &opts).map_err(|e| e.kind)?;
+        assert_eq!(gen.rule, rule);
+
+        #[derive(Debug, Eq, PartialEq)]
+        struct Data {
+            b: Vec<u8>,
+            s: Vec<u8>,
+        }
+
+        impl ParseNtLife for Data {
+            fn from_bs(b: Vec<u8>, s: Vec<u8>) -> Self {
+                Data { b, s }
+            }
+        }
+
+        assert_eq!(
+            Data::parse_rule("B2kc/S32")?,
+            Data::parse_rule("B2-aein/S23")?
+        );
+        assert_eq!(Data::parse_rule("B2kc/S32")?.b, rule.b);
+        Ok(())
+    }
+
+    #[test]
+    fn extreme_counts() -> Result<(), ParseRuleError> {
+        let opts = ParseOptions::default();
+        let rule = NtLife::parse_rule_with("B8/S", &opts).map_err(|e| e.kind)?;
+        assert_eq!(rule.b, vec![0xff]);
+        assert!(rule.s.is_empty());
+        let rule = NtLife::parse_rule_with("B0/S8c", &opts).map_err(|e| e.kind)?;
+        assert_eq!(rule.b, vec![0x00]);
+        assert_eq!(rule.s, vec![0xff]);
+        assert_eq!(
+            NtLife::parse_rule_with("B0c/S8c", &opts).map_err(|e| e.kind)?,
+            rule
+        );
+        let rule: NtLife = ParseLife::parse_rule("B8/S")?;
+        assert_eq!(rule.b, vec![0xff]);
+        Ok(())
+    }
+
+    #[test]
+    fn hensel_tables() -> Result<(), ParseRuleError> {
+        let opts = ParseOptions::default();
+        // Each letter of each count gives distinct neighborhoods with that many neighbors,
+        // and the letters of each count cover all of them.
+        for n in 0..=8 {
+            let mut all = Vec::new();
+            for letter in "cekainyqjrtwz".chars() {
+                if let Ok(rule) = NtLife::parse_rule_with(&format!("B{}{}/S", n, letter), &opts) {
+                    assert!(rule.b.iter().all(|b| b.count_ones() == n));
+                    all.extend(rule.b);
+                }
+            }
+            let len = all.len();
+            all.sort_unstable();
+            all.dedup();
+            assert_eq!(all.len(), len);
+            let mut masks: Vec<u8> = NTLIFE_NEIGHBOR_MASKS[n as usize]
+                .iter()
+                .flat_map(|&(_, masks)| masks.iter().copied())
+                .collect();
+            masks.sort_unstable();
+            assert_eq!(all, masks);
+            assert_eq!(
+                all,
+                NtLife::parse_rule_with(&format!("B{}/S", n), &opts)
+                    .map_err(|e| e.kind)?
+                    .b
+            );
+            assert_eq!(
+                all.len(),
+                (0..=0xffu8).filter(|b| b.count_ones() == n).count()
+            );
+        }
+        Ok(())
     }
 
     #[test]
@@ -303,6 +654,31 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn parse_life_gen_as_ntlife() -> Result<(), ParseRuleError> {
+        struct GenRule(usize);
+
+        impl ParseLifeGen for GenRule {
+            fn from_bsg(_b: Vec<u8>, _s: Vec<u8>, gen: usize) -> Self {
+                GenRule(gen)
+            }
+        }
+
+        impl ParseNtLifeGen for GenRule {
+            fn from_bsg(_b: Vec<u8>, _s: Vec<u8>, gen: usize) -> Self {
+                GenRule(gen)
+            }
+        }
+
+        for input in ["B3/S23c3", "b3s23c3"] {
+            let GenRule(life) = ParseLifeGen::parse_rule(input)?;
+            let GenRule(ntlife) = ParseNtLifeGen::parse_rule(input)?;
+            assert_eq!(life, 3);
+            assert_eq!(ntlife, life);
+        }
+        Ok(())
+    }
+
     #[test]
     fn parse_hex_as_ntlife() -> Result<(), ParseRuleError> {
         let rule: NtLife = ParseNtHex::parse_rule("B2/S34H")?;
@@ -343,17 +719,265 @@ mod tests {
 
     #[test]
     fn parse_map() -> Result<(), ParseRuleError> {
-        let rule1: NtLife = NtLife::parse_rule("B3/S23")?;
-        let rule2: NtLife = NtLife::parse_rule_map("MAPARYXfhZofugWaH7oaIDogBZofuhogOiAaIDogIAAgAAWaH7oaIDogGiA6ICAAIAAaIDogIAAgACAAIAAAAAAAA")?;
+        let opts = ParseOptions::default();
+        let rule1: NtLife = NtLife::parse_rule_with("B3/S23", &opts).map_err(|e| e.kind)?;
+        let rule2: NtLife = NtLife::parse_rule_map_with("MAPARYXfhZofugWaH7oaIDogBZofuhogOiAaIDogIAAgAAWaH7oaIDogGiA6ICAAIAAaIDogIAAgACAAIAAAAAAAA", &opts).map_err(|e| e.kind)?;
         assert_eq!(rule1, rule2);
+        let rule3: NtLife = NtLife::parse_rule_map_with("MAPARYXfhZofugWaH7oaIDogBZofuhogOiAaIDogIAAgAAWaH7oaIDogGiA6ICAAIAAaIDogIAAgACAAIAAAAAAAA==", &opts).map_err(|e| e.kind)?;
+        assert_eq!(rule1, rule3);
+        assert_eq!(
+            NtLife::parse_rule_map_with("MAPARYXfhZofugWaH7oaIDogBZofuhogOiAaIDogIAAgAAWaH7oaIDogGiA6ICAAIAAaIDogIAAgACAAIAAAAAAAA==A", &opts).map_err(|e| e.kind).err(),
+            Some(ParseRuleError::Base64Error(MapDecodeError::InvalidLength))
+        );
+        assert_eq!(
+            NtLife::parse_rule_map_with("MAPARYXfhZofugWaH7oaIDogBZofuhogOiAaIDogIAAgAAWaH7oaIDogGiA6ICAAIAAaIDogIAAgACAAIAAAAAAAA===", &opts).map_err(|e| e.kind).err(),
+            Some(ParseRuleError::Base64Error(MapDecodeError::InvalidLength))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn map_whitespace() -> Result<(), ParseRuleError> {
+        let opts = ParseOptions::default();
+        let rule = NtLife::parse_rule_map_with("MAPARYXfhZofugWaH7oaIDogBZofuhogOiAaIDogIAAgAAWaH7oaIDogGiA6ICAAIAAaIDogIAAgACAAIAAAAAAAA", &opts).map_err(|e| e.kind)?;
+        assert_eq!(
+            NtLife::parse_rule_map_with(
+                "MAPARYXfhZofugWaH7oaIDogBZofuhogOiAaIDogIAAg\n\
+                 AAWaH7oaIDogGiA6ICAAIAAaIDogIAAgACAAIAAAAAAAA",
+                &opts
+            )
+            .map_err(|e| e.kind)?,
+            rule
+        );
+        assert_eq!(
+            NtLife::parse_rule_map_with("MAP ARYXfhZofugWaH7o\taIDogBZofuhogOiAaIDogIAAgAAWaH7oaIDogGiA6ICAAIAAaIDogIAAgACAAIAAAAAA\r\nAA", &opts).map_err(|e| e.kind)?,
+            rule
+        );
+        assert_eq!(
+            NtLife::parse_rule_map_with("MAPARYXfhZofugWaH7oaIDogBZofuhogOiAaIDogIAAgAAWaH7oaIDogGiA6ICAAIAAaIDogIAAgACAAIAAAAAAA A==", &opts).map_err(|e| e.kind)?,
+            rule
+        );
+        let gen: Gen<NtLife> = NtLife::parse_rule_gen_map_with(
+            "MAPARYBFxZpF38WaRd/aZZ//hZpF39pln/+aZZ//pZp/ukWaRd/aZZ//mmWf/6W\n\
+             af7paZZ//pZp/umWaf7paZbplg/5",
+            &opts,
+        )
+        .map_err(|e| e.kind)?;
+        assert_eq!(gen.gen, 5);
+        assert_eq!(
+            NtLife::parse_rule_gen_map_with("MAPARYBFxZpF38WaRd/aZZ//hZpF39pln/+aZZ//pZp/ukWaRd/aZZ//mmWf/6Waf7paZZ//pZp/umWaf7paZbplg/ 5", &opts).map_err(|e| e.kind)
+            .err(),
+            Some(ParseRuleError::MissingNumber)
+        );
         Ok(())
     }
 
     #[test]
     fn parse_gen_map() -> Result<(), ParseRuleError> {
-        let rule1: Gen<NtLife> = NtLife::parse_rule_gen("3457/357/5")?;
-        let rule2: Gen<NtLife> = NtLife::parse_rule_gen_map("MAPARYBFxZpF38WaRd/aZZ//hZpF39pln/+aZZ//pZp/ukWaRd/aZZ//mmWf/6Waf7paZZ//pZp/umWaf7paZbplg/5")?;
+        let opts = ParseOptions::default();
+        let rule1: Gen<NtLife> =
+            NtLife::parse_rule_gen_with("3457/357/5", &opts).map_err(|e| e.kind)?;
+        let rule2: Gen<NtLife> = NtLife::parse_rule_gen_map_with("MAPARYBFxZpF38WaRd/aZZ//hZpF39pln/+aZZ//pZp/ukWaRd/aZZ//mmWf/6Waf7paZZ//pZp/umWaf7paZbplg/5", &opts).map_err(|e| e.kind)?;
         assert_eq!(rule1, rule2);
+        let rule3: Gen<NtLife> = NtLife::parse_rule_gen_map_with("MAPARYBFxZpF38WaRd_aZZ__hZpF39pln_-aZZ__pZp_ukWaRd_aZZ__mmWf_6Waf7paZZ__pZp_umWaf7paZbplg/5", &opts).map_err(|e| e.kind)?;
+        assert_eq!(rule1, rule3);
+        let rule4: Gen<NtLife> = parse_rule_gen("g5MAPARYBFxZpF38WaRd/aZZ//hZpF39pln/+aZZ//pZp/ukWaRd/aZZ//mmWf/6Waf7paZZ//pZp/umWaf7paZbplg", &ParseOptions::default()).map_err(|e| e.kind)?;
+        assert_eq!(rule1, rule4);
+        assert_eq!(
+            NtLife::parse_rule_gen_with("B2i34cj/S23/C99999999999999999999999", &opts)
+                .map_err(|e| e.kind)
+                .err(),
+            Some(ParseRuleError::GenOverflow)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn empty_rule() -> Result<(), ParseRuleError> {
+        for input in [
+            "B/S",
+            "/",
+            "bs",
+            "MAPAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA",
+        ] {
+            assert_eq!(
+                Rule::parse_rule(input)?,
+                Rule {
+                    b: vec![],
+                    s: vec![]
+                },
+                "{:?}",
+                input
+            );
+        }
+        for input in [
+            "B/S/C3",
+            "//3",
+            "g3bs",
+            "MAPAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA/3",
+            "g3MAPAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA",
+        ] {
+            assert_eq!(
+                GenRule::parse_rule(input)?,
+                GenRule {
+                    b: vec![],
+                    s: vec![],
+                    gen: 3
+                },
+                "{:?}",
+                input
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn surrounding_whitespace() -> Result<(), ParseRuleError> {
+        for input in [
+            " B2ci3ai4c8/S02ae3eijkq4iz5ar6i7e",
+            "B2-a/S12\r\n",
+            "\tMAPARYXfhZofugWaH7oaIDogBZofuhogOiAaIDogIAAgAAWaH7oaIDogGiA6ICAAIAAaIDogIAAgACAAIAAAAAAAA ",
+        ] {
+            assert_eq!(
+                Rule::parse_rule(input)?,
+                Rule::parse_rule(input.trim())?,
+                "{:?}",
+                input
+            );
+        }
+        for input in [
+            " MAPARYBFxZpF38WaRd/aZZ//hZpF39pln/+aZZ//pZp/ukWaRd/aZZ//mmWf/6Waf7paZZ//pZp/umWaf7paZbplg/5\n",
+            "\tg3b2-a3s23 ",
+        ] {
+            assert_eq!(
+                GenRule::parse_rule(input)?,
+                GenRule::parse_rule(input.trim())?,
+                "{:?}",
+                input
+            );
+        }
+        assert_eq!(
+            Rule::parse_rule("B2-a /S12").err(),
+            Some(ParseRuleError::Missing('S'))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn trailing_slash() {
+        assert_eq!(
+            Rule::parse_rule("B3/S23/").err(),
+            Some(ParseRuleError::extra_junk('/', 6))
+        );
+        assert_eq!(
+            Rule::parse_rule("B2c/S23/").err(),
+            Some(ParseRuleError::extra_junk('/', 7))
+        );
+        assert_eq!(
+            GenRule::parse_rule("B3/S23/").err(),
+            Some(ParseRuleError::MissingNumber)
+        );
+        assert_eq!(
+            GenRule::parse_rule("B2c/S23/").err(),
+            Some(ParseRuleError::MissingNumber)
+        );
+        assert_eq!(
+            GenRule::parse_rule("g3b3s23/").err(),
+            Some(ParseRuleError::extra_junk('/', 7))
+        );
+        assert_eq!(
+            GenRule::parse_rule("MAPARYXfhZofugWaH7oaIDogBZofuhogOiAaIDogIAAgAAWaH7oaIDogGiA6ICAAIAAaIDogIAAgACAAIAAAAAAAA/").err(),
+            Some(ParseRuleError::MissingNumber)
+        );
+    }
+
+    #[test]
+    fn case_sensitive() -> Result<(), ParseRuleError> {
+        let opts = ParseOptions {
+            case_sensitive: true,
+            ..ParseOptions::default()
+        };
+        Rule::parse_rule_with("B2c3-cn/S2-c34c", &opts)?;
+        Rule::parse_rule_with("MAPARYXfhZofugWaH7oaIDogBZofuhogOiAaIDogIAAgAAWaH7oaIDogGiA6ICAAIAAaIDogIAAgACAAIAAAAAAAA", &opts)?;
+        GenRule::parse_rule_with("B2c3/S2c/C3", &opts)?;
+        GenRule::parse_rule_with("MAPARYBFxZpF38WaRd/aZZ//hZpF39pln/+aZZ//pZp/ukWaRd/aZZ//mmWf/6Waf7paZZ//pZp/umWaf7paZbplgC5", &opts)?;
+        assert_eq!(
+            Rule::parse_rule_with("b2c3-cn/S2-c34c", &opts).err(),
+            Some(ParseRuleError::Unexpected('b'))
+        );
+        assert_eq!(
+            Rule::parse_rule_with("mapARYXfhZofugWaH7oaIDogBZofuhogOiAaIDogIAAgAAWaH7oaIDogGiA6ICAAIAAaIDogIAAgACAAIAAAAAAAA", &opts).err(),
+            Some(ParseRuleError::Unexpected('m'))
+        );
+        assert_eq!(
+            GenRule::parse_rule_with("B2c3/S2c/c3", &opts).err(),
+            Some(ParseRuleError::Unexpected('c'))
+        );
+        assert_eq!(
+            GenRule::parse_rule_with("MAPARYBFxZpF38WaRd/aZZ//hZpF39pln/+aZZ//pZp/ukWaRd/aZZ//mmWf/6Waf7paZZ//pZp/umWaf7paZbplgc5", &opts).err(),
+            Some(ParseRuleError::Unexpected('c'))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn sb_notation() -> Result<(), ParseRuleError> {
+        let opts = ParseOptions {
+            allow_sb_notation: false,
+            ..ParseOptions::default()
+        };
+        Rule::parse_rule_with("2-c34c/2c3-cn", &ParseOptions::default())?;
+        Rule::parse_rule_with("B2c3-cn/S2-c34c", &opts)?;
+        Rule::parse_rule_with("MAPARYXfhZofugWaH7oaIDogBZofuhogOiAaIDogIAAgAAWaH7oaIDogGiA6ICAAIAAaIDogIAAgACAAIAAAAAAAA", &opts)?;
+        GenRule::parse_rule_with("B2c/S2c/C3", &opts)?;
+        assert_eq!(
+            Rule::parse_rule_with("2-c34c/2c3-cn", &opts).err(),
+            Some(ParseRuleError::Missing('B'))
+        );
+        assert_eq!(
+            Rule::parse_rule_with("23/2H", &opts).err(),
+            Some(ParseRuleError::Missing('B'))
+        );
+        assert_eq!(
+            GenRule::parse_rule_with("2c/2c/3", &opts).err(),
+            Some(ParseRuleError::Missing('B'))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn error_offsets() {
+        let at = ParseError::at;
+        let e = Rule::parse_rule_at("B2q/S23").err().unwrap();
+        assert_eq!(e, at(ParseRuleError::Unexpected('q'), 2));
+        assert_eq!(e.to_string(), "Unexpected 'q' at byte 2");
+        assert_eq!(Rule::parse_rule("B2q/S23").err(), Some(e.kind));
+        assert_eq!(
+            Rule::parse_rule_at("B3/S8-").err(),
+            Some(at(ParseRuleError::EmptyNegation(8), 6))
+        );
+        assert_eq!(
+            Rule::parse_rule_at("MAPHmlp").err(),
+            Some(at(ParseRuleError::UnknownMapLength { found: 24 }, 3))
+        );
+    }
+
+    #[test]
+    fn lifeviewer() -> Result<(), ParseRuleError> {
+        let opts = ParseOptions {
+            lifeviewer: true,
+            ..ParseOptions::default()
+        };
+        assert_eq!(
+            Rule::parse_rule_with("B2e3_S2-i34qNM", &opts)?,
+            Rule::parse_rule("B2e3/S2-i34q")?
+        );
+        assert!(Rule::parse_rule("B2e3_S2-i34qNM").is_err());
+        assert_eq!(
+            GenRule::parse_rule_with("B2e_S23C4", &opts)?,
+            GenRule::parse_rule("B2e/S23/C4")?
+        );
+        assert!(GenRule::parse_rule("B2e_S23C4").is_err());
         Ok(())
     }
 }
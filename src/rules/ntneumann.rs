@@ -4,7 +4,7 @@ use super::{
     neumann::{ParseNeumann, ParseNeumannGen},
     Gen,
 };
-use crate::ParseRuleError;
+use crate::{ParseError, ParseOptions, ParseRuleError};
 
 rule_struct!(NtNeumann);
 
@@ -65,9 +65,33 @@ pub trait ParseNtNeumann {
     where
         Self: Sized,
     {
-        let NtNeumann { b, s } = ParseNeumann::parse_rule(input).or_else(|e| {
-            NtNeumann::parse_rule_map(input).map_err(|e_map| {
-                if e_map == ParseRuleError::NotMapRule {
+        Self::parse_rule_with(input, &ParseOptions::default())
+    }
+
+    /// The parser, with the given options.
+    fn parse_rule_with(input: &str, opts: &ParseOptions) -> Result<Self, ParseRuleError>
+    where
+        Self: Sized,
+    {
+        Self::parse_rule_at_with(input, opts).map_err(|e| e.kind)
+    }
+
+    /// The parser, returning the byte offset of the error.
+    fn parse_rule_at(input: &str) -> Result<Self, ParseError>
+    where
+        Self: Sized,
+    {
+        Self::parse_rule_at_with(input, &ParseOptions::default())
+    }
+
+    /// The parser, with the given options, returning the byte offset of the error.
+    fn parse_rule_at_with(input: &str, opts: &ParseOptions) -> Result<Self, ParseError>
+    where
+        Self: Sized,
+    {
+        let NtNeumann { b, s } = ParseNeumann::parse_rule_at_with(input, opts).or_else(|e| {
+            NtNeumann::parse_rule_map_with(input, opts).map_err(|e_map| {
+                if e_map.kind == ParseRuleError::NotMapRule {
                     e
                 } else {
                     e_map
@@ -123,34 +147,95 @@ pub trait ParseNtNeumannGen {
 
     /// The parser.
     fn parse_rule(input: &str) -> Result<Self, ParseRuleError>
+    where
+        Self: Sized,
+    {
+        Self::parse_rule_with(input, &ParseOptions::default())
+    }
+
+    /// The parser, with the given options.
+    fn parse_rule_with(input: &str, opts: &ParseOptions) -> Result<Self, ParseRuleError>
+    where
+        Self: Sized,
+    {
+        Self::parse_rule_at_with(input, opts).map_err(|e| e.kind)
+    }
+
+    /// The parser, returning the byte offset of the error.
+    fn parse_rule_at(input: &str) -> Result<Self, ParseError>
+    where
+        Self: Sized,
+    {
+        Self::parse_rule_at_with(input, &ParseOptions::default())
+    }
+
+    /// The parser, with the given options, returning the byte offset of the error.
+    fn parse_rule_at_with(input: &str, opts: &ParseOptions) -> Result<Self, ParseError>
     where
         Self: Sized,
     {
         let Gen {
             rule: NtNeumann { b, s },
             gen,
-        } = ParseNeumannGen::parse_rule(input).or_else(|e| {
-            NtNeumann::parse_rule_gen_map(input).map_err(|e_map| {
-                if e_map == ParseRuleError::NotMapRule {
-                    e
-                } else {
-                    e_map
-                }
-            })
-        })?;
+        } = parse_rule_gen(input, opts)?;
         Ok(Self::from_bsg(b, s, gen))
     }
+
+    /// A lenient parser that treats a number of states less than 2 as 2,
+    /// so that strings like `B2/S013V/C1` or `MAPHmlphg/1`, which some programs write
+    /// for rules with two states, are accepted.
+    fn parse_rule_lenient(input: &str) -> Result<Self, ParseRuleError>
+    where
+        Self: Sized,
+    {
+        let opts = ParseOptions {
+            lenient: true,
+            ..ParseOptions::default()
+        };
+        Self::parse_rule_with(input, &opts)
+    }
+}
+
+/// Tries each parser for Generations rules in turn.
+fn parse_rule_gen(input: &str, opts: &ParseOptions) -> Result<Gen<NtNeumann>, ParseError> {
+    ParseNeumannGen::parse_rule_at_with(input, opts).or_else(|e| {
+        NtNeumann::parse_rule_gen_map_with(input, opts).map_err(|e_map| {
+            if e_map.kind == ParseRuleError::NotMapRule {
+                e
+            } else {
+                e_map
+            }
+        })
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::MapDecodeError;
 
-    struct Rule;
+    #[derive(Debug, Eq, PartialEq)]
+    struct Rule {
+        b: Vec<u8>,
+        s: Vec<u8>,
+    }
 
     impl ParseNtNeumann for Rule {
-        fn from_bs(_b: Vec<u8>, _s: Vec<u8>) -> Self {
-            Rule
+        fn from_bs(b: Vec<u8>, s: Vec<u8>) -> Self {
+            Rule { b, s }
+        }
+    }
+
+    #[derive(Debug, Eq, PartialEq)]
+    struct GenRule {
+        b: Vec<u8>,
+        s: Vec<u8>,
+        gen: usize,
+    }
+
+    impl ParseNtNeumannGen for GenRule {
+        fn from_bsg(b: Vec<u8>, s: Vec<u8>, gen: usize) -> Self {
+            GenRule { b, s, gen }
         }
     }
 
@@ -169,7 +254,7 @@ mod tests {
     fn invalid_rules() {
         assert_eq!(
             Rule::parse_rule("B3/S23va").err(),
-            Some(ParseRuleError::ExtraJunk)
+            Some(ParseRuleError::extra_junk('a', 7))
         );
         assert_eq!(
             Rule::parse_rule("B3V/S23").err(),
@@ -181,7 +266,7 @@ mod tests {
         );
         assert_eq!(
             Rule::parse_rule("B3/S25V").err(),
-            Some(ParseRuleError::Missing('V'))
+            Some(ParseRuleError::CountTooLarge(5))
         );
         assert_eq!(
             Rule::parse_rule("233v").err(),
@@ -189,7 +274,10 @@ mod tests {
         );
         assert_eq!(
             Rule::parse_rule("MAPFgFoF2gXgH5oF4B+gH4A6A").err(),
-            Some(ParseRuleError::InvalidLength)
+            Some(ParseRuleError::InvalidLength {
+                expected: 0x20,
+                found: 0x80
+            })
         );
     }
 
@@ -208,9 +296,382 @@ mod tests {
 
     #[test]
     fn parse_map() -> Result<(), ParseRuleError> {
+        let opts = ParseOptions::default();
         let rule1: NtNeumann = NtNeumann::parse_rule("B2/S013V")?;
-        let rule2: NtNeumann = NtNeumann::parse_rule_map("MAPHmlphg")?;
+        let rule2: NtNeumann =
+            NtNeumann::parse_rule_map_with("MAPHmlphg", &opts).map_err(|e| e.kind)?;
         assert_eq!(rule1, rule2);
+        let rule3: NtNeumann =
+            NtNeumann::parse_rule_map_with("mapHmlphg", &opts).map_err(|e| e.kind)?;
+        assert_eq!(rule1, rule3);
+        let rule4: NtNeumann =
+            NtNeumann::parse_rule_map_with("MaPHmlphg", &opts).map_err(|e| e.kind)?;
+        assert_eq!(rule1, rule4);
+        let rule5: NtNeumann =
+            NtNeumann::parse_rule_map_with("MAPHmlphg==", &opts).map_err(|e| e.kind)?;
+        assert_eq!(rule1, rule5);
+        assert_eq!(
+            NtNeumann::parse_rule_map_with("MAPHmlphg===", &opts)
+                .map_err(|e| e.kind)
+                .err(),
+            Some(ParseRuleError::Base64Error(MapDecodeError::InvalidLength))
+        );
+        assert_eq!(
+            NtNeumann::parse_rule_map_with("MAPHmlp=hg=", &opts)
+                .map_err(|e| e.kind)
+                .err(),
+            Some(ParseRuleError::Base64Error(MapDecodeError::InvalidByte(
+                4, b'='
+            )))
+        );
+        assert_eq!(
+            NtNeumann::parse_rule_map_with("B2/S013V", &opts)
+                .map_err(|e| e.kind)
+                .err(),
+            Some(ParseRuleError::NotMapRule)
+        );
+        Rule::parse_rule("mapHmlphg")?;
+        assert_eq!(
+            Rule::parse_rule("B2/S013").err(),
+            Some(ParseRuleError::Missing('V'))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn parse_gen_map() -> Result<(), ParseRuleError> {
+        let opts = ParseOptions::default();
+        let lenient = ParseOptions {
+            lenient: true,
+            ..ParseOptions::default()
+        };
+        let rule: Gen<NtNeumann> =
+            NtNeumann::parse_rule_gen_map_with("MAPHmlphg/3", &opts).map_err(|e| e.kind)?;
+        assert_eq!(rule.gen, 3);
+        assert_eq!(
+            rule.rule,
+            NtNeumann::parse_rule_map_with("MAPHmlphg", &opts).map_err(|e| e.kind)?
+        );
+        let rule: Gen<NtNeumann> =
+            NtNeumann::parse_rule_gen_map_with("MAPHmlphg==/3", &opts).map_err(|e| e.kind)?;
+        assert_eq!(rule.gen, 3);
+
+        // A slash inside the data, right before the last character.
+        let rule: Gen<NtNeumann> =
+            NtNeumann::parse_rule_gen_map_with("MAPHmlp/g", &opts).map_err(|e| e.kind)?;
+        assert_eq!(rule.gen, 2);
+        assert_eq!(
+            rule.rule,
+            NtNeumann::parse_rule_map_with("MAPHmlp/g", &opts).map_err(|e| e.kind)?
+        );
+        let rule: Gen<NtNeumann> =
+            NtNeumann::parse_rule_gen_map_with("MAPHmlp/g/4", &opts).map_err(|e| e.kind)?;
+        assert_eq!(rule.gen, 4);
+        assert_eq!(
+            NtNeumann::parse_rule_gen_map_with("MAPHmlp/g/", &opts)
+                .map_err(|e| e.kind)
+                .err(),
+            Some(ParseRuleError::MissingNumber)
+        );
+
+        for input in [
+            "MAPHmlphg/G5",
+            "MAPHmlphg/g5",
+            "MAPHmlphg/C5",
+            "MAPHmlphg/c5",
+            "MAPHmlphgG5",
+            "MAPHmlphgc5",
+            "MAPHmlphg==/G5",
+            "MAPHmlphg==C5",
+        ] {
+            let rule: Gen<NtNeumann> =
+                NtNeumann::parse_rule_gen_map_with(input, &opts).map_err(|e| e.kind)?;
+            assert_eq!(rule.gen, 5);
+            assert_eq!(
+                rule.rule,
+                NtNeumann::parse_rule_map_with("MAPHmlphg", &opts).map_err(|e| e.kind)?
+            );
+        }
+        let rule: Gen<NtNeumann> =
+            NtNeumann::parse_rule_gen_map_with("g5MAPHmlphg", &opts).map_err(|e| e.kind)?;
+        assert_eq!(rule.gen, 5);
+        assert_eq!(
+            rule.rule,
+            NtNeumann::parse_rule_map_with("MAPHmlphg", &opts).map_err(|e| e.kind)?
+        );
+        let rule: Gen<NtNeumann> =
+            NtNeumann::parse_rule_gen_map_with("G12mapHmlphg==", &opts).map_err(|e| e.kind)?;
+        assert_eq!(rule.gen, 12);
+        assert_eq!(
+            NtNeumann::parse_rule_gen_map_with("g1MAPHmlphg", &opts)
+                .map_err(|e| e.kind)
+                .err(),
+            Some(ParseRuleError::GenLessThan2)
+        );
+        assert_eq!(
+            NtNeumann::parse_rule_gen_map_with("g1MAPHmlphg", &lenient)
+                .map_err(|e| e.kind)?
+                .gen,
+            2
+        );
+        assert_eq!(
+            NtNeumann::parse_rule_gen_map_with("g5MAPHmlphg/5", &opts)
+                .map_err(|e| e.kind)
+                .err(),
+            Some(ParseRuleError::extra_junk('/', 11))
+        );
+        assert_eq!(
+            NtNeumann::parse_rule_gen_map_with("gMAPHmlphg", &opts)
+                .map_err(|e| e.kind)
+                .err(),
+            Some(ParseRuleError::NotMapRule)
+        );
+        assert_eq!(
+            NtNeumann::parse_rule_gen_map_with("MAPHmlphg/GG5", &opts)
+                .map_err(|e| e.kind)
+                .err(),
+            Some(ParseRuleError::MissingNumber)
+        );
+        assert_eq!(
+            NtNeumann::parse_rule_gen_map_with("MAPHmlphg/G", &opts)
+                .map_err(|e| e.kind)
+                .err(),
+            Some(ParseRuleError::MissingNumber)
+        );
+        assert_eq!(
+            NtNeumann::parse_rule_gen_map_with("MAPHmlp/3", &opts)
+                .map_err(|e| e.kind)
+                .err(),
+            Some(ParseRuleError::Base64Error(
+                MapDecodeError::InvalidLastSymbol(5, 51)
+            ))
+        );
+        assert_eq!(
+            NtNeumann::parse_rule_gen_map_with("MAPHmlphg/3/", &opts)
+                .map_err(|e| e.kind)
+                .err(),
+            Some(ParseRuleError::extra_junk('/', 11))
+        );
+        assert_eq!(
+            NtNeumann::parse_rule_gen_map_with("MAPHmlphg/99999999999999999999999", &opts)
+                .map_err(|e| e.kind)
+                .err(),
+            Some(ParseRuleError::GenOverflow)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn empty_rule() -> Result<(), ParseRuleError> {
+        for input in ["B/SV", "/V", "bsv", "MAPAAAAAA"] {
+            assert_eq!(
+                Rule::parse_rule(input)?,
+                Rule {
+                    b: vec![],
+                    s: vec![]
+                },
+                "{:?}",
+                input
+            );
+        }
+        for input in ["B/S/C3V", "//3V", "g3bsv", "MAPAAAAAA/3", "g3MAPAAAAAA"] {
+            assert_eq!(
+                GenRule::parse_rule(input)?,
+                GenRule {
+                    b: vec![],
+                    s: vec![],
+                    gen: 3
+                },
+                "{:?}",
+                input
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn surrounding_whitespace() -> Result<(), ParseRuleError> {
+        for input in [" B2/S013V", "B2/S013V\n", "\tMAPHmlphg "] {
+            assert_eq!(
+                Rule::parse_rule(input)?,
+                Rule::parse_rule(input.trim())?,
+                "{:?}",
+                input
+            );
+        }
+        for input in [" MAPHmlphg/3\n", "\tg3b2s013v "] {
+            assert_eq!(
+                GenRule::parse_rule(input)?,
+                GenRule::parse_rule(input.trim())?,
+                "{:?}",
+                input
+            );
+        }
+        for input in ["MAPHml phg", "MAP Hmlphg"] {
+            assert_eq!(
+                Rule::parse_rule(input)?,
+                Rule::parse_rule("MAPHmlphg")?,
+                "{:?}",
+                input
+            );
+        }
+        assert_eq!(
+            Rule::parse_rule("B2/S013 V").err(),
+            Some(ParseRuleError::Missing('V'))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn empty_input() {
+        for input in ["", " ", "\t\r\n"] {
+            assert_eq!(
+                Rule::parse_rule(input).err(),
+                Some(ParseRuleError::EmptyInput)
+            );
+            assert_eq!(
+                GenRule::parse_rule(input).err(),
+                Some(ParseRuleError::EmptyInput)
+            );
+            assert_eq!(
+                GenRule::parse_rule_lenient(input).err(),
+                Some(ParseRuleError::EmptyInput)
+            );
+        }
+    }
+
+    #[test]
+    fn trailing_slash() {
+        assert_eq!(
+            Rule::parse_rule("B3/S23V/").err(),
+            Some(ParseRuleError::extra_junk('/', 7))
+        );
+        for input in ["B3/S23V/", "23/3V/", "MAPHmlphg/", "MAPHmlphgC"] {
+            assert_eq!(
+                GenRule::parse_rule(input).err(),
+                Some(ParseRuleError::MissingNumber),
+                "{:?}",
+                input
+            );
+        }
+        assert_eq!(
+            GenRule::parse_rule("g3b3s23v/").err(),
+            Some(ParseRuleError::extra_junk('/', 8))
+        );
+        assert_eq!(
+            GenRule::parse_rule("g3MAPHmlphg/").err(),
+            Some(ParseRuleError::extra_junk('/', 11))
+        );
+    }
+
+    #[test]
+    fn case_sensitive() -> Result<(), ParseRuleError> {
+        let opts = ParseOptions {
+            case_sensitive: true,
+            ..ParseOptions::default()
+        };
+        Rule::parse_rule_with("MAPHmlphg", &opts)?;
+        GenRule::parse_rule_with("MAPHmlphg/G3", &opts)?;
+        assert_eq!(
+            Rule::parse_rule_with("B2/s013V", &opts).err(),
+            Some(ParseRuleError::Unexpected('s'))
+        );
+        assert_eq!(
+            Rule::parse_rule_with("mAPHmlphg", &opts).err(),
+            Some(ParseRuleError::Unexpected('m'))
+        );
+        assert_eq!(
+            GenRule::parse_rule_with("MAPHmlphg/c3", &opts).err(),
+            Some(ParseRuleError::Unexpected('c'))
+        );
+        Rule::parse_rule_with("mAPHmlphg", &ParseOptions::default())?;
+        Ok(())
+    }
+
+    #[test]
+    fn sb_notation() -> Result<(), ParseRuleError> {
+        let opts = ParseOptions {
+            allow_sb_notation: false,
+            ..ParseOptions::default()
+        };
+        Rule::parse_rule_with("MAPHmlphg", &opts)?;
+        GenRule::parse_rule_with("MAPHmlphg/3", &opts)?;
+        assert_eq!(
+            Rule::parse_rule_with("013/2V", &opts).err(),
+            Some(ParseRuleError::Missing('B'))
+        );
+        assert_eq!(
+            GenRule::parse_rule_with("013/2/3V", &opts).err(),
+            Some(ParseRuleError::Missing('B'))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn suffix_order_gen() -> Result<(), ParseRuleError> {
+        let rule = GenRule::parse_rule("B2/S013/C4V")?;
+        for input in [
+            "B2/S013/4V",
+            "B2/S013C4V",
+            "B2/S013V/C4",
+            "B2/S013V/4",
+            "B2/S013VC4",
+            "B2/S013V4",
+            "b2s013vg4",
+            "013/2/4V",
+            "013/2/C4V",
+            "013/2V/4",
+            "013/2V/G4",
+            "013/2V4",
+            "013/2v4",
+            "g4b2s013v",
+        ] {
+            assert_eq!(GenRule::parse_rule(input)?, rule, "{}", input);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn error_offsets() {
+        let at = ParseError::at;
+        assert_eq!(
+            Rule::parse_rule_at("MAPHm lp!hg").err(),
+            Some(at(
+                ParseRuleError::Base64Error(MapDecodeError::InvalidByte(4, b'!')),
+                8
+            ))
+        );
+        assert_eq!(
+            Rule::parse_rule_at("MAPHmlp").err(),
+            Some(at(
+                ParseRuleError::InvalidLength {
+                    expected: 32,
+                    found: 24,
+                },
+                3
+            ))
+        );
+        assert_eq!(
+            GenRule::parse_rule_at("MAPHmlphg/1").err(),
+            Some(at(ParseRuleError::GenLessThan2, 10))
+        );
+        assert_eq!(
+            GenRule::parse_rule_at("g1MAPHmlphg").err(),
+            Some(at(ParseRuleError::GenLessThan2, 1))
+        );
+    }
+
+    #[test]
+    fn lifeviewer() -> Result<(), ParseRuleError> {
+        let opts = ParseOptions {
+            lifeviewer: true,
+            ..ParseOptions::default()
+        };
+        // MAP strings are not rewritten.
+        assert_eq!(
+            Rule::parse_rule_with("MAPHmlphg", &opts)?,
+            Rule::parse_rule("MAPHmlphg")?
+        );
         Ok(())
     }
 }
@@ -0,0 +1,287 @@
+//! Bounded grids.
+
+use crate::ParseRuleError;
+use std::{
+    fmt::{self, Display, Formatter},
+    iter::Peekable,
+    str::{Chars, FromStr},
+};
+
+/// The shape of a [bounded grid](http://golly.sourceforge.net/Help/bounded.html).
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum TopologyKind {
+    /// A plane with no joined edges, written as `P`.
+    Plane,
+    /// A torus, written as `T`.
+    Torus,
+    /// A Klein bottle, written as `K`.
+    KleinBottle,
+    /// A cross-surface, written as `C`.
+    CrossSurface,
+    /// A sphere, written as `S`.
+    Sphere,
+}
+
+impl TopologyKind {
+    fn letter(self) -> char {
+        match self {
+            TopologyKind::Plane => 'P',
+            TopologyKind::Torus => 'T',
+            TopologyKind::KleinBottle => 'K',
+            TopologyKind::CrossSurface => 'C',
+            TopologyKind::Sphere => 'S',
+        }
+    }
+}
+
+/// A [bounded grid](http://golly.sourceforge.net/Help/bounded.html) specification,
+/// i.e., the part after the `:` in rule strings like `B3/S23:T100,100`.
+///
+/// The general form is `<kind><width>[*][+/-shift],<height>[*][+/-shift]`:
+///
+/// * The height can be omitted, in which case it is the same as the width.
+///   A sphere can only have one dimension.
+/// * A zero width or height means that the plane or torus is infinite in that direction.
+/// * A Klein bottle must have exactly one twisted pair of edges, marked by `*`.
+///   No other kind of grid may have a twist.
+/// * A torus or a Klein bottle may shift the cells along one pair of edges when joining them.
+///
+/// # Examples
+///
+/// ```
+/// use ca_rules::{Topology, TopologyKind};
+///
+/// let (rule, topology) = Topology::split("B3/S23:T100+5,50").unwrap();
+/// let topology = topology.unwrap();
+///
+/// assert_eq!(rule, "B3/S23");
+/// assert_eq!(topology.kind, TopologyKind::Torus);
+/// assert_eq!((topology.width, topology.height), (100, 50));
+/// assert_eq!(topology.shift_x, 5);
+/// assert_eq!(topology.to_string(), "T100+5,50");
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct Topology {
+    /// The shape of the grid.
+    pub kind: TopologyKind,
+    /// The width of the grid.
+    pub width: usize,
+    /// The height of the grid.
+    pub height: usize,
+    /// Whether the pair of edges following the width is twisted.
+    pub twist_x: bool,
+    /// Whether the pair of edges following the height is twisted.
+    pub twist_y: bool,
+    /// The shift following the width.
+    pub shift_x: isize,
+    /// The shift following the height.
+    pub shift_y: isize,
+}
+
+impl Topology {
+    /// Splits a rule string into the rule itself and the optional bounded grid
+    /// specification following the `:`.
+    pub fn split(input: &str) -> Result<(&str, Option<Self>), ParseRuleError> {
+        match input.split_once(':') {
+            Some((rule, topology)) => Ok((rule, Some(topology.trim_end().parse()?))),
+            None => Ok((input, None)),
+        }
+    }
+
+    /// A parser for one dimension of the grid, including the twist and the shift.
+    fn parse_dimension(
+        chars: &mut Peekable<Chars>,
+    ) -> Result<(usize, bool, isize), ParseRuleError> {
+        let size = Self::parse_num(chars)?;
+        let twist = chars.peek() == Some(&'*');
+        if twist {
+            chars.next();
+        }
+        let shift = match chars.peek() {
+            Some('+') => {
+                chars.next();
+                Self::parse_num(chars)? as isize
+            }
+            Some('-') => {
+                chars.next();
+                -(Self::parse_num(chars)? as isize)
+            }
+            _ => 0,
+        };
+        Ok((size, twist, shift))
+    }
+
+    /// A parser for numbers.
+    fn parse_num(chars: &mut Peekable<Chars>) -> Result<usize, ParseRuleError> {
+        let mut n: usize = 0;
+        if !matches!(chars.peek(), Some(c) if c.is_ascii_digit()) {
+            return Err(ParseRuleError::InvalidTopology);
+        }
+        while let Some(d) = chars.peek().and_then(|c| c.to_digit(10)) {
+            chars.next();
+            n = n
+                .checked_mul(10)
+                .and_then(|n| n.checked_add(d as usize))
+                .filter(|&n| n <= isize::MAX as usize)
+                .ok_or(ParseRuleError::InvalidTopology)?;
+        }
+        Ok(n)
+    }
+}
+
+impl FromStr for Topology {
+    type Err = ParseRuleError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut chars = input.chars().peekable();
+        let kind = match chars.next() {
+            Some('P') | Some('p') => TopologyKind::Plane,
+            Some('T') | Some('t') => TopologyKind::Torus,
+            Some('K') | Some('k') => TopologyKind::KleinBottle,
+            Some('C') | Some('c') => TopologyKind::CrossSurface,
+            Some('S') | Some('s') => TopologyKind::Sphere,
+            _ => return Err(ParseRuleError::InvalidTopology),
+        };
+        let (width, twist_x, shift_x) = Self::parse_dimension(&mut chars)?;
+        let (height, twist_y, shift_y) = if chars.peek() == Some(&',') {
+            chars.next();
+            Self::parse_dimension(&mut chars)?
+        } else {
+            (width, false, 0)
+        };
+        if chars.next().is_some() {
+            return Err(ParseRuleError::InvalidTopology);
+        }
+
+        let valid = match kind {
+            TopologyKind::Plane => !twist_x && !twist_y && shift_x == 0 && shift_y == 0,
+            TopologyKind::Torus => !twist_x && !twist_y && (shift_x == 0 || shift_y == 0),
+            TopologyKind::KleinBottle => {
+                twist_x != twist_y && (shift_x == 0 || shift_y == 0) && width > 0 && height > 0
+            }
+            TopologyKind::CrossSurface => {
+                !twist_x && !twist_y && shift_x == 0 && shift_y == 0 && width > 0 && height > 0
+            }
+            TopologyKind::Sphere => {
+                !twist_x && !twist_y && shift_x == 0 && shift_y == 0 && width > 0 && width == height
+            }
+        };
+        if valid {
+            Ok(Topology {
+                kind,
+                width,
+                height,
+                twist_x,
+                twist_y,
+                shift_x,
+                shift_y,
+            })
+        } else {
+            Err(ParseRuleError::InvalidTopology)
+        }
+    }
+}
+
+impl Display for Topology {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        fn write_dimension(
+            f: &mut Formatter,
+            size: usize,
+            twist: bool,
+            shift: isize,
+        ) -> fmt::Result {
+            write!(f, "{}", size)?;
+            if twist {
+                write!(f, "*")?;
+            }
+            if shift != 0 {
+                write!(f, "{:+}", shift)?;
+            }
+            Ok(())
+        }
+
+        write!(f, "{}", self.kind.letter())?;
+        write_dimension(f, self.width, self.twist_x, self.shift_x)?;
+        if self.kind != TopologyKind::Sphere {
+            write!(f, ",")?;
+            write_dimension(f, self.height, self.twist_y, self.shift_y)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid_topologies() -> Result<(), ParseRuleError> {
+        for s in [
+            "P30,20",
+            "P0,20",
+            "T100,100",
+            "T100+5,50",
+            "T100,50-3",
+            "K40*,30",
+            "K40,30*+2",
+            "C50,40",
+            "S50",
+        ] {
+            assert_eq!(s.parse::<Topology>()?.to_string(), s);
+        }
+        assert_eq!("t30".parse::<Topology>()?.to_string(), "T30,30");
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_topologies() {
+        for s in [
+            "",
+            "T",
+            "X10,10",
+            "T10,",
+            "T10,10,10",
+            "T10+1,10+1",
+            "P10+1,10",
+            "K10,10",
+            "K10*,10*",
+            "T10*,10",
+            "S10,20",
+            "S0",
+            "C0,10",
+            "T10,10a",
+            "T99999999999999999999,10",
+        ] {
+            assert_eq!(
+                s.parse::<Topology>().err(),
+                Some(ParseRuleError::InvalidTopology),
+                "{:?}",
+                s
+            );
+        }
+    }
+
+    #[test]
+    fn split() -> Result<(), ParseRuleError> {
+        assert_eq!(Topology::split("B3/S23")?, ("B3/S23", None));
+        let (rule, topology) = Topology::split("B3/S23:P30,20")?;
+        assert_eq!(rule, "B3/S23");
+        assert_eq!(
+            topology,
+            Some(Topology {
+                kind: TopologyKind::Plane,
+                width: 30,
+                height: 20,
+                twist_x: false,
+                twist_y: false,
+                shift_x: 0,
+                shift_y: 0,
+            })
+        );
+        assert_eq!(
+            Topology::split("B3/S23:").err(),
+            Some(ParseRuleError::InvalidTopology)
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,113 @@
+//! Multistate variants of two-state rules.
+
+use std::fmt::{self, Display, Formatter};
+
+/// A multistate variant layered over a two-state rule,
+/// written as a suffix of the rule string, e.g., `B3/S23History`.
+///
+/// These suffixes are only meaningful for non-Generations rules,
+/// so the Generations parsers reject them with [`ParseRuleError::VariantInGen`](crate::ParseRuleError::VariantInGen).
+///
+/// # Examples
+///
+/// ```
+/// use ca_rules::Variant;
+///
+/// assert_eq!(Variant::split("B3/S23History"), ("B3/S23", Variant::History));
+/// assert_eq!(Variant::split("b3s23super"), ("b3s23", Variant::Super));
+/// assert_eq!(Variant::split("B3/S23"), ("B3/S23", Variant::None));
+/// assert_eq!(Variant::Investigator.suffix_catagolue(), "investigator");
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+pub enum Variant {
+    /// No suffix.
+    #[default]
+    None,
+    /// [History](http://www.conwaylife.com/wiki/LifeHistory) rules, suffix `History`.
+    History,
+    /// [Super](http://www.conwaylife.com/wiki/LifeSuper) rules, suffix `Super`.
+    Super,
+    /// [Investigator](http://www.conwaylife.com/wiki/StateInvestigator) rules, suffix `Investigator`.
+    Investigator,
+}
+
+impl Variant {
+    const ALL: [Variant; 3] = [Variant::History, Variant::Super, Variant::Investigator];
+
+    /// Splits a rule string into the base rule and the variant suffix.
+    ///
+    /// The suffix is matched case-insensitively.
+    pub fn split(input: &str) -> (&str, Self) {
+        let trimmed = input.trim_end_matches(|c: char| c.is_ascii_whitespace());
+        for variant in Self::ALL {
+            let suffix = variant.suffix();
+            if let Some(n) = trimmed.len().checked_sub(suffix.len()) {
+                if trimmed.is_char_boundary(n) && trimmed[n..].eq_ignore_ascii_case(suffix) {
+                    return (&trimmed[..n], variant);
+                }
+            }
+        }
+        (input, Variant::None)
+    }
+
+    /// The suffix used in B/S and S/B notations, e.g., `History`.
+    pub fn suffix(self) -> &'static str {
+        match self {
+            Variant::None => "",
+            Variant::History => "History",
+            Variant::Super => "Super",
+            Variant::Investigator => "Investigator",
+        }
+    }
+
+    /// The suffix used in the notation of Catagolue, e.g., `history`.
+    pub fn suffix_catagolue(self) -> &'static str {
+        match self {
+            Variant::None => "",
+            Variant::History => "history",
+            Variant::Super => "super",
+            Variant::Investigator => "investigator",
+        }
+    }
+}
+
+impl Display for Variant {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str(self.suffix())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split() {
+        assert_eq!(
+            Variant::split("B3/S23History"),
+            ("B3/S23", Variant::History)
+        );
+        assert_eq!(
+            Variant::split("B3/S23HISTORY"),
+            ("B3/S23", Variant::History)
+        );
+        assert_eq!(Variant::split("b3s23super"), ("b3s23", Variant::Super));
+        assert_eq!(
+            Variant::split("B2/S34HInvestigator\n"),
+            ("B2/S34H", Variant::Investigator)
+        );
+        assert_eq!(Variant::split("B3/S23"), ("B3/S23", Variant::None));
+        assert_eq!(Variant::split("uper"), ("uper", Variant::None));
+        assert_eq!(Variant::split("Super"), ("", Variant::Super));
+    }
+
+    #[test]
+    fn suffix() {
+        assert_eq!(format!("B3/S23{}", Variant::History), "B3/S23History");
+        assert_eq!(
+            format!("b3s23{}", Variant::Super.suffix_catagolue()),
+            "b3s23super"
+        );
+        assert_eq!(Variant::None.to_string(), "");
+    }
+}